@@ -0,0 +1,31 @@
+use slam_viewer::{Alignment, Association, Comparison, Trajectory};
+
+fn main() -> failure::Fallible<()> {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    if args.len() < 2 {
+        eprintln!("usage: compare <groundtruth.txt> <estimate.txt> [--sim3]");
+        std::process::exit(1);
+    }
+
+    let ground_truth = Trajectory::load(&args[0])?;
+    let estimate = Trajectory::load(&args[1])?;
+
+    let alignment = if args.iter().any(|a| a == "--sim3") {
+        Alignment::Sim3
+    } else {
+        Alignment::SE3
+    };
+
+    // TUM timestamps are in seconds; KITTI poses are paired by index
+    let association = Association::Timestamp {
+        max_difference: 0.02,
+    };
+
+    let comparison = Comparison::new(&ground_truth, &estimate, association, alignment)?;
+    println!("{}", comparison);
+
+    slam_viewer::alloc_thread::<f32>()
+        .add_comparison(&comparison)
+        .run();
+    Ok(())
+}
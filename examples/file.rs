@@ -0,0 +1,17 @@
+fn main() -> failure::Fallible<()> {
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: file <map.ply|map.pcd|map.xyz>");
+            std::process::exit(1);
+        }
+    };
+
+    // make a window with this thread
+    slam_viewer::alloc_thread::<f32>()
+        .add_point_cloud_file(path)?
+        .with_camera_mode(slam_viewer::CameraMode::Orbit)
+        .on_pick(|pick| println!("point {} at {:?}", pick.index, pick.position))
+        .run();
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use nalgebra::Point3;
+use rand_distr::{Distribution, StandardNormal};
+
+fn main() -> failure::Fallible<()> {
+    const NUM_POINTS: usize = 10_000;
+
+    let mut rng = rand::thread_rng();
+    let mut rng = StandardNormal.sample_iter(&mut rng);
+
+    let points = (0..NUM_POINTS)
+        .map(|_| {
+            let x = rng.next().unwrap();
+            let y = rng.next().unwrap();
+            let z = rng.next().unwrap();
+            Point3::new(x, y, z - 10.0)
+        })
+        .collect();
+
+    // render without any window
+    let images = slam_viewer::alloc_thread()
+        .add_points(points)
+        .render_offscreen(800, 600)?;
+
+    for (i, image) in images.iter().enumerate() {
+        image.save(format!("snapshot_{}.png", i))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use nalgebra::{Isometry3, Point3, Translation3, UnitQuaternion, Vector3};
+use rand_distr::{Distribution, StandardNormal};
+use slam_viewer::{CameraIntrinsics, ViewerMessage};
+
+fn main() {
+    const NUM_POINTS_PER_FRAME: usize = 500;
+    const NUM_FRAMES: usize = 100;
+
+    let mut rng = rand::thread_rng();
+    let mut rng = StandardNormal.sample_iter(&mut rng);
+
+    // make a window with a new thread
+    let (engine, sender) = slam_viewer::alloc_thread().add_live().spawn();
+
+    // press `T` to follow the keyframes, or to look through them
+    sender.send(ViewerMessage::Intrinsics(CameraIntrinsics {
+        fx: 525.0,
+        fy: 525.0,
+        cx: 319.5,
+        cy: 239.5,
+        width: 640,
+        height: 480,
+    }));
+
+    // the keyframes look along `-z`, towards the landmarks
+    let rotation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), std::f32::consts::PI);
+
+    let mut tz = 0.0;
+    for _ in 0..NUM_FRAMES {
+        tz -= 0.5;
+        let keyframe = Isometry3::from_parts(Translation3::new(0.0, 0.0, tz), rotation);
+
+        let landmarks = (0..NUM_POINTS_PER_FRAME)
+            .map(|_| {
+                let x = rng.next().unwrap();
+                let y = rng.next().unwrap();
+                let z = rng.next().unwrap();
+                Point3::new(x, y, z + tz - 10.0)
+            })
+            .collect();
+
+        sender.send(ViewerMessage::KeyFrames(vec![keyframe]));
+        if !sender.send(ViewerMessage::Landmarks(landmarks)) {
+            break;
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    engine.wait();
+}
@@ -1,5 +1,6 @@
 use nalgebra::Point3;
 use rand_distr::{Distribution, StandardNormal};
+use slam_viewer::Colormap;
 
 fn main() {
     const NUM_POINTS: usize = 10_000;
@@ -7,7 +8,7 @@ fn main() {
     let mut rng = rand::thread_rng();
     let mut rng = StandardNormal.sample_iter(&mut rng);
 
-    let points = (0..NUM_POINTS)
+    let points: Vec<_> = (0..NUM_POINTS)
         .map(|_| {
             let x = rng.next().unwrap();
             let y = rng.next().unwrap();
@@ -16,6 +17,13 @@ fn main() {
         })
         .collect();
 
+    // color the points by their distance from the center
+    let distances: Vec<f32> = points
+        .iter()
+        .map(|p| (p - Point3::new(0.0, 0.0, -10.0)).norm())
+        .collect();
+    let colors = Colormap::Turbo.map_values(&distances);
+
     #[cfg(target_arch = "wasm32")]
     {
         std::panic::set_hook(Box::new(console_error_panic_hook::hook));
@@ -23,5 +31,7 @@ fn main() {
     }
 
     // make a window with this thread
-    slam_viewer::alloc_thread().add_points(points).run();
+    slam_viewer::alloc_thread()
+        .add_colored_points(points, colors)
+        .run();
 }
@@ -139,5 +139,5 @@ fn main() {
     slam_viewer::alloc_thread().add_world(world).run();
 
     // make a window with a new thread
-    // slam_viewer::alloc_thread().add_world(world).spawn().wait();
+    // slam_viewer::alloc_thread().add_world(world).spawn().0.wait();
 }
@@ -0,0 +1,15 @@
+fn main() -> failure::Fallible<()> {
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("usage: trajectory <poses.txt>");
+            std::process::exit(1);
+        }
+    };
+
+    // TUM (`timestamp tx ty tz qx qy qz qw`) or KITTI (3x4 row-major)
+    slam_viewer::alloc_thread::<f32>()
+        .add_trajectory_file(path)?
+        .run();
+    Ok(())
+}
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
+use std::sync::mpsc;
 
 use super::base::Engine;
 use super::timer::Timer;
 use crate::pipes::{PipelineBuilder, VertexFormat};
-use crate::window::{WindowBuilder, WindowEventState};
+use crate::window::{WindowBuilder, WindowCommand, WindowEventState};
 
 #[cfg(not(target_arch = "wasm32"))]
 use futures::executor::block_on;
@@ -24,6 +25,7 @@ where
     Point3<N>: VertexFormat<N>,
 {
     pub windows: Vec<(WindowBuilder<N>, Box<dyn PipelineBuilder<N>>)>,
+    pub commands: mpsc::Receiver<WindowCommand>,
 }
 
 impl<N> EngineBuilder<N>
@@ -44,10 +46,15 @@ where
     }
 
     async fn run_forever(self, event_loop: EventLoop<()>) {
+        let commands = self.commands;
+
         let mut windows = HashMap::new();
+        // in the order of the viewer, to tell their files apart
+        let mut ids = vec![];
         for (builder, pipe) in self.windows {
             let (id, window) = builder.build(&event_loop, pipe).await;
             windows.insert(id, window);
+            ids.push(id);
         }
 
         let mut timer = windows
@@ -67,19 +74,11 @@ where
                     window_id,
                 } => {
                     if let Some(window) = windows.get_mut(&window_id) {
-                        if window.input(event) == WindowEventState::Unused {
-                            match event {
+                        match window.input(event) {
+                            WindowEventState::Exit => *control_flow = ControlFlow::Exit,
+                            WindowEventState::Consumed => {}
+                            WindowEventState::Unused => match event {
                                 WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                                WindowEvent::KeyboardInput { input, .. } => {
-                                    if let KeyboardInput {
-                                        state: ElementState::Pressed,
-                                        virtual_keycode: Some(VirtualKeyCode::Escape),
-                                        ..
-                                    } = input
-                                    {
-                                        *control_flow = ControlFlow::Exit
-                                    }
-                                }
                                 WindowEvent::Resized(physical_size) => {
                                     window.resize(*physical_size);
                                 }
@@ -88,7 +87,7 @@ where
                                     window.resize(**new_inner_size);
                                 }
                                 _ => {}
-                            }
+                            },
                         }
                     }
                 }
@@ -103,7 +102,16 @@ where
                         timer.sync();
                     }
 
-                    for engine_window in windows.values() {
+                    for command in commands.try_iter() {
+                        for (index, id) in ids.iter().enumerate() {
+                            if let Some(engine_window) = windows.get_mut(id) {
+                                engine_window.command(command.for_window(index, ids.len()));
+                            }
+                        }
+                    }
+
+                    for engine_window in windows.values_mut() {
+                        engine_window.sync();
                         engine_window.request_redraw();
                     }
                 }
@@ -1,7 +1,9 @@
 mod base;
 mod builder;
 mod event_loop;
+mod sender;
 mod timer;
 
 pub use self::base::Engine;
 pub use self::builder::EngineBuilder;
+pub use self::sender::ViewerSender;
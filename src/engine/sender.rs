@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use crate::pipes::LayerChange;
+use crate::window::{models::ViewerMessage, WindowCommand};
+
+use slam_cv::Number;
+
+/// A handle to push live updates into the windows of a running viewer.
+///
+/// Every message is delivered to all the live windows of the viewer.
+#[derive(Clone)]
+pub struct ViewerSender<N>
+where
+    N: 'static + Number,
+{
+    senders: Vec<mpsc::Sender<ViewerMessage<N>>>,
+    commands: mpsc::Sender<WindowCommand>,
+}
+
+impl<N> ViewerSender<N>
+where
+    N: 'static + Number,
+{
+    pub(crate) fn new(
+        senders: Vec<mpsc::Sender<ViewerMessage<N>>>,
+        commands: mpsc::Sender<WindowCommand>,
+    ) -> Self {
+        Self { senders, commands }
+    }
+
+    /// Returns `false` if all the live windows are already closed.
+    pub fn send(&self, message: ViewerMessage<N>) -> bool {
+        self.senders
+            .iter()
+            .filter(|sender| sender.send(message.clone()).is_ok())
+            .count()
+            > 0
+    }
+
+    /// Saves the next frame of every window as a PNG file.
+    ///
+    /// With several windows, the index of each window is appended to the file name,
+    /// e.g. `map_0.png` and `map_1.png`. The failures are given to `Viewer::on_error`.
+    pub fn screenshot<P: Into<PathBuf>>(&self, path: P) -> bool {
+        self.command(WindowCommand::Screenshot(path.into()))
+    }
+
+    /// Saves every frame into the directory, as a numbered image sequence.
+    ///
+    /// With several windows, the index of each window is appended to the directory, as for
+    /// `screenshot`.
+    pub fn start_recording<P: Into<PathBuf>>(&self, directory: P) -> bool {
+        self.command(WindowCommand::StartRecording(directory.into()))
+    }
+
+    pub fn stop_recording(&self) -> bool {
+        self.command(WindowCommand::StopRecording)
+    }
+
+    /// Shows the layers of the name, e.g. `"Landmarks"`, in every window.
+    ///
+    /// Like the other changes of the layers, this returns `false` only if all the windows are
+    /// already closed; the windows without a layer of the name are left as they are.
+    pub fn show_layer(&self, name: &str) -> bool {
+        self.layer(name, LayerChange::Visible(true))
+    }
+
+    pub fn hide_layer(&self, name: &str) -> bool {
+        self.layer(name, LayerChange::Visible(false))
+    }
+
+    /// Paints the layers of the name with the color, or with their own colors if `None`.
+    pub fn set_layer_color(&self, name: &str, color: Option<[f32; 3]>) -> bool {
+        self.layer(name, LayerChange::Color(color))
+    }
+
+    /// Scales the depth of the frustums of the layers of the name, e.g. `"Keyframes"`.
+    pub fn set_frustum_scale(&self, name: &str, scale: f32) -> bool {
+        self.layer(name, LayerChange::FrustumScale(scale))
+    }
+
+    fn layer(&self, name: &str, change: LayerChange) -> bool {
+        self.command(WindowCommand::Layer(name.to_string(), change))
+    }
+
+    fn command(&self, command: WindowCommand) -> bool {
+        self.commands.send(command).is_ok()
+    }
+}
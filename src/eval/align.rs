@@ -0,0 +1,195 @@
+use failure::{bail, Fallible};
+use nalgebra::{Isometry3, Matrix3, Point3, Rotation3, Translation3, UnitQuaternion, Vector3};
+
+/// How the estimate is moved onto the ground truth before measuring the errors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    /// Compares the poses as they are.
+    None,
+    /// A rigid transform, for metric estimates (e.g. stereo, RGB-D).
+    SE3,
+    /// A rigid transform with a scale, for monocular estimates.
+    Sim3,
+}
+
+/// A similarity transform, `p -> scale * (rotation * p) + translation`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Similarity {
+    pub rotation: UnitQuaternion<f64>,
+    pub translation: Vector3<f64>,
+    pub scale: f64,
+}
+
+impl Default for Similarity {
+    fn default() -> Self {
+        Self {
+            rotation: UnitQuaternion::identity(),
+            translation: Vector3::zeros(),
+            scale: 1.0,
+        }
+    }
+}
+
+impl Similarity {
+    /// Estimates the transform from `from` onto `to` with the method of Umeyama (1991).
+    pub fn umeyama(from: &[Point3<f64>], to: &[Point3<f64>], with_scale: bool) -> Fallible<Self> {
+        if from.len() != to.len() {
+            bail!("mismatched point sets: {} and {}", from.len(), to.len());
+        }
+        if from.len() < 3 {
+            bail!("at least 3 pairs are needed for the alignment");
+        }
+        let n = from.len() as f64;
+
+        let mean = |points: &[Point3<f64>]| {
+            points
+                .iter()
+                .fold(Vector3::zeros(), |sum, p| sum + p.coords)
+                / n
+        };
+        let mean_from = mean(from);
+        let mean_to = mean(to);
+
+        let mut covariance = Matrix3::zeros();
+        let mut variance_from = 0.0;
+        for (p, q) in from.iter().zip(to) {
+            let p = p.coords - mean_from;
+            let q = q.coords - mean_to;
+            covariance += q * p.transpose();
+            variance_from += p.norm_squared();
+        }
+        covariance /= n;
+        variance_from /= n;
+
+        let svd = covariance.svd(true, true);
+        let (u, v_t) = match (svd.u, svd.v_t) {
+            (Some(u), Some(v_t)) => (u, v_t),
+            _ => bail!("failed to decompose the covariance"),
+        };
+
+        // avoid a reflection, flipping the weakest direction, e.g. the normal of a planar
+        // trajectory; the singular values are not sorted
+        let mut s = Vector3::new(1.0, 1.0, 1.0);
+        if u.determinant() * v_t.determinant() < 0.0 {
+            s[svd.singular_values.imin()] = -1.0;
+        }
+
+        let rotation = u * Matrix3::from_diagonal(&s) * v_t;
+        let rotation =
+            UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(rotation));
+
+        let scale = if with_scale {
+            if variance_from <= std::f64::EPSILON {
+                bail!("degenerate trajectory for a scale alignment");
+            }
+            svd.singular_values.dot(&s) / variance_from
+        } else {
+            1.0
+        };
+
+        let translation = mean_to - scale * (rotation * mean_from);
+
+        Ok(Self {
+            rotation,
+            translation,
+            scale,
+        })
+    }
+
+    pub fn transform_point(&self, point: &Point3<f64>) -> Point3<f64> {
+        Point3::from(self.scale * (self.rotation * point.coords) + self.translation)
+    }
+
+    /// Moves a camera pose; the scale only applies to its position.
+    pub fn transform_pose(&self, pose: &Isometry3<f64>) -> Isometry3<f64> {
+        let position = self.transform_point(&Point3::from(pose.translation.vector));
+        Isometry3::from_parts(
+            Translation3::from(position.coords),
+            self.rotation * pose.rotation,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Unit;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn similarity() -> Similarity {
+        Similarity {
+            rotation: UnitQuaternion::from_axis_angle(
+                &Unit::new_normalize(Vector3::new(1.0, -2.0, 0.5)),
+                2.0,
+            ),
+            translation: Vector3::new(3.0, -1.0, 4.0),
+            scale: 2.5,
+        }
+    }
+
+    fn cloud(planar: bool) -> Vec<Point3<f64>> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..100)
+            .map(|_| {
+                let z = if planar {
+                    0.0
+                } else {
+                    rng.gen_range(-5.0, 5.0)
+                };
+                Point3::new(rng.gen_range(-5.0, 5.0), rng.gen_range(-5.0, 5.0), z)
+            })
+            .collect()
+    }
+
+    fn assert_recovered(from: &[Point3<f64>], expected: &Similarity, with_scale: bool) {
+        let to: Vec<_> = from.iter().map(|p| expected.transform_point(p)).collect();
+        let similarity = Similarity::umeyama(from, &to, with_scale).unwrap();
+
+        assert!(similarity.rotation.angle_to(&expected.rotation) < 1e-9);
+        assert!((similarity.translation - expected.translation).norm() < 1e-9);
+        assert!((similarity.scale - expected.scale).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sim3() {
+        assert_recovered(&cloud(false), &similarity(), true);
+    }
+
+    #[test]
+    fn sim3_planar() {
+        // the covariance is singular, so a reflection fits as well as the rotation
+        assert_recovered(&cloud(true), &similarity(), true);
+
+        // the same plane, seen in every orientation
+        for axis in &[Vector3::x_axis(), Vector3::y_axis(), Vector3::z_axis()] {
+            for i in 0..8 {
+                let expected = Similarity {
+                    rotation: UnitQuaternion::from_axis_angle(axis, i as f64 * 0.8),
+                    ..similarity()
+                };
+                assert_recovered(&cloud(true), &expected, true);
+            }
+        }
+    }
+
+    #[test]
+    fn se3() {
+        let expected = Similarity {
+            scale: 1.0,
+            ..similarity()
+        };
+        assert_recovered(&cloud(false), &expected, false);
+        assert_recovered(&cloud(true), &expected, false);
+    }
+
+    #[test]
+    fn invalid() {
+        let points = cloud(false);
+        assert!(Similarity::umeyama(&points[..2], &points[..2], true).is_err());
+        assert!(Similarity::umeyama(&points[..3], &points[..4], true).is_err());
+
+        let same = vec![Point3::new(1.0, 2.0, 3.0); 5];
+        assert!(Similarity::umeyama(&same, &same, true).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Evaluation of an estimated trajectory against the ground truth.
+
+mod align;
+mod stats;
+
+pub use self::align::{Alignment, Similarity};
+pub use self::stats::ErrorStats;
+
+use crate::io::Trajectory;
+
+use failure::{bail, Fallible};
+use nalgebra::{Isometry3, Point3};
+
+/// How the poses of two trajectories are paired.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Association {
+    /// Pairs the poses in order, ignoring the extra poses of the longer one.
+    Index,
+    /// Pairs each estimated pose with the nearest ground truth in time.
+    ///
+    /// **caution**: The timestamps should be sorted.
+    Timestamp { max_difference: f64 },
+}
+
+/// The associated, aligned poses and their errors.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub ground_truth: Vec<Isometry3<f64>>,
+    /// The estimated poses moved onto the ground truth.
+    pub estimate: Vec<Isometry3<f64>>,
+    /// The transform applied to the estimate.
+    pub alignment: Similarity,
+
+    /// The distance between the positions of each pair.
+    pub errors: Vec<f64>,
+    /// The absolute trajectory error, in the unit of the ground truth.
+    pub ate: ErrorStats,
+    /// The translational relative pose error between consecutive pairs.
+    pub rpe_translation: ErrorStats,
+    /// The rotational relative pose error between consecutive pairs, in radians.
+    pub rpe_rotation: ErrorStats,
+}
+
+impl Comparison {
+    pub fn new(
+        ground_truth: &Trajectory,
+        estimate: &Trajectory,
+        association: Association,
+        alignment: Alignment,
+    ) -> Fallible<Self> {
+        let pairs = match association {
+            Association::Index => (0..ground_truth.len().min(estimate.len()))
+                .map(|i| (i, i))
+                .collect(),
+            Association::Timestamp { max_difference } => associate_timestamps(
+                &ground_truth.timestamps,
+                &estimate.timestamps,
+                max_difference,
+            ),
+        };
+
+        Self::from_poses(
+            pairs.iter().map(|&(i, _)| ground_truth.poses[i]).collect(),
+            pairs.iter().map(|&(_, j)| estimate.poses[j]).collect(),
+            alignment,
+        )
+    }
+
+    /// Compares the already associated poses.
+    pub fn from_poses(
+        ground_truth: Vec<Isometry3<f64>>,
+        estimate: Vec<Isometry3<f64>>,
+        alignment: Alignment,
+    ) -> Fallible<Self> {
+        if ground_truth.len() != estimate.len() {
+            bail!(
+                "mismatched trajectories: {} and {} poses",
+                ground_truth.len(),
+                estimate.len(),
+            );
+        }
+        if ground_truth.is_empty() {
+            bail!("no associated poses");
+        }
+
+        let positions = |poses: &[Isometry3<f64>]| -> Vec<Point3<f64>> {
+            poses
+                .iter()
+                .map(|pose| pose.translation.vector.into())
+                .collect()
+        };
+
+        let alignment = match alignment {
+            Alignment::None => Similarity::default(),
+            Alignment::SE3 | Alignment::Sim3 => Similarity::umeyama(
+                &positions(&estimate),
+                &positions(&ground_truth),
+                alignment == Alignment::Sim3,
+            )?,
+        };
+        let estimate: Vec<_> = estimate
+            .iter()
+            .map(|pose| alignment.transform_pose(pose))
+            .collect();
+
+        let errors: Vec<_> = ground_truth
+            .iter()
+            .zip(&estimate)
+            .map(|(gt, est)| (gt.translation.vector - est.translation.vector).norm())
+            .collect();
+
+        let (rpe_translation, rpe_rotation): (Vec<_>, Vec<_>) = ground_truth
+            .windows(2)
+            .zip(estimate.windows(2))
+            .map(|(gt, est)| {
+                let error = (gt[0].inverse() * gt[1]).inverse() * (est[0].inverse() * est[1]);
+                (error.translation.vector.norm(), error.rotation.angle())
+            })
+            .unzip();
+
+        Ok(Self {
+            ate: ErrorStats::new(&errors),
+            rpe_translation: ErrorStats::new(&rpe_translation),
+            rpe_rotation: ErrorStats::new(&rpe_rotation),
+
+            ground_truth,
+            estimate,
+            alignment,
+            errors,
+        })
+    }
+}
+
+impl core::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "pairs: {}", self.errors.len())?;
+        writeln!(f, "scale: {:.6}", self.alignment.scale)?;
+        writeln!(f, "ATE: {}", self.ate)?;
+        writeln!(f, "RPE (translation): {}", self.rpe_translation)?;
+        write!(f, "RPE (rotation, rad): {}", self.rpe_rotation)
+    }
+}
+
+/// Pairs each estimated timestamp with the nearest unused ground truth one.
+fn associate_timestamps(
+    ground_truth: &[f64],
+    estimate: &[f64],
+    max_difference: f64,
+) -> Vec<(usize, usize)> {
+    let mut pairs: Vec<(usize, usize)> = vec![];
+
+    for (j, &t) in estimate.iter().enumerate() {
+        let i = match ground_truth
+            .binary_search_by(|gt| gt.partial_cmp(&t).unwrap_or(core::cmp::Ordering::Equal))
+        {
+            Ok(i) => i,
+            Err(i) => {
+                let before = i.checked_sub(1);
+                let after = Some(i).filter(|&i| i < ground_truth.len());
+                match (before, after) {
+                    (Some(b), Some(a)) if t - ground_truth[b] <= ground_truth[a] - t => b,
+                    (_, Some(a)) => a,
+                    (Some(b), None) => b,
+                    (None, None) => continue,
+                }
+            }
+        };
+
+        let is_used = pairs.last().map(|&(last, _)| last >= i).unwrap_or(false);
+        if (ground_truth[i] - t).abs() <= max_difference && !is_used {
+            pairs.push((i, j));
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::{UnitQuaternion, Vector3};
+
+    /// A trajectory turning around, and going up.
+    fn ground_truth() -> Vec<Isometry3<f64>> {
+        (0..20)
+            .map(|i| {
+                let t = i as f64 * 0.3;
+                Isometry3::new(
+                    Vector3::new(t.cos() * 4.0, t * 0.5, t.sin() * 4.0),
+                    Vector3::y() * t,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn associate() {
+        let ground_truth = [0.0, 1.0, 2.0, 3.0];
+        let estimate = [0.1, 0.9, 1.05, 2.6, 3.0, 10.0];
+
+        // 1.05 is nearest to a used one, and 2.6 too far from any
+        assert_eq!(
+            associate_timestamps(&ground_truth, &estimate, 0.2),
+            vec![(0, 0), (1, 1), (3, 4)]
+        );
+        assert_eq!(associate_timestamps(&[], &estimate, 0.2), vec![]);
+    }
+
+    #[test]
+    fn offset_without_alignment() {
+        let ground_truth = ground_truth();
+        let estimate = ground_truth
+            .iter()
+            .map(|pose| Isometry3::translation(0.0, 0.0, 1.0) * pose)
+            .collect();
+
+        let comparison = Comparison::from_poses(ground_truth, estimate, Alignment::None).unwrap();
+
+        assert!((comparison.ate.rmse - 1.0).abs() < 1e-12);
+        assert!((comparison.ate.std).abs() < 1e-6);
+        // the relative poses are the same
+        assert!(comparison.rpe_translation.max < 1e-12);
+        assert!(comparison.rpe_rotation.max < 1e-6);
+    }
+
+    #[test]
+    fn aligned_sim3() {
+        let ground_truth = ground_truth();
+        let alignment = Similarity {
+            rotation: UnitQuaternion::from_euler_angles(0.3, -1.2, 2.0),
+            translation: Vector3::new(-2.0, 5.0, 1.0),
+            scale: 0.2,
+        };
+        let inverse = |pose: &Isometry3<f64>| {
+            let position = alignment.rotation.inverse()
+                * (pose.translation.vector - alignment.translation)
+                / alignment.scale;
+            Isometry3::from_parts(
+                position.into(),
+                alignment.rotation.inverse() * pose.rotation,
+            )
+        };
+        let estimate: Vec<_> = ground_truth.iter().map(inverse).collect();
+
+        let comparison =
+            Comparison::from_poses(ground_truth.clone(), estimate.clone(), Alignment::Sim3)
+                .unwrap();
+        assert!((comparison.alignment.scale - alignment.scale).abs() < 1e-9);
+        assert!(comparison.ate.max < 1e-9);
+        assert!(comparison.rpe_translation.max < 1e-9);
+
+        // the scale is left as an error
+        let comparison = Comparison::from_poses(ground_truth, estimate, Alignment::SE3).unwrap();
+        assert_eq!(comparison.alignment.scale, 1.0);
+        assert!(comparison.ate.rmse > 1.0);
+    }
+
+    #[test]
+    fn invalid() {
+        let ground_truth = ground_truth();
+        assert!(Comparison::from_poses(ground_truth.clone(), vec![], Alignment::None).is_err());
+        assert!(Comparison::from_poses(vec![], vec![], Alignment::None).is_err());
+    }
+}
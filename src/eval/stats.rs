@@ -0,0 +1,78 @@
+/// The statistics of the errors, as reported by the usual evaluation scripts.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct ErrorStats {
+    pub rmse: f64,
+    pub mean: f64,
+    pub median: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ErrorStats {
+    pub fn new(errors: &[f64]) -> Self {
+        if errors.is_empty() {
+            return Self::default();
+        }
+        let n = errors.len() as f64;
+
+        let mut sorted = errors.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+
+        let mid = sorted.len() / 2;
+        let median = if sorted.len() % 2 == 0 {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        };
+
+        let mean = errors.iter().sum::<f64>() / n;
+        let squared = errors.iter().map(|e| e * e).sum::<f64>() / n;
+
+        Self {
+            rmse: squared.sqrt(),
+            mean,
+            median,
+            std: (squared - mean * mean).max(0.0).sqrt(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+impl core::fmt::Display for ErrorStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "rmse {:.6}, mean {:.6}, median {:.6}, std {:.6}, min {:.6}, max {:.6}",
+            self.rmse, self.mean, self.median, self.std, self.min, self.max,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let stats = ErrorStats::new(&[4.0, 1.0, 3.0, 2.0]);
+
+        assert_eq!(stats.mean, 2.5);
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.rmse, 7.5f64.sqrt());
+        assert!((stats.std - 1.25f64.sqrt()).abs() < 1e-12);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.max, 4.0);
+    }
+
+    #[test]
+    fn median_of_odd() {
+        assert_eq!(ErrorStats::new(&[5.0, 1.0, 2.0]).median, 2.0);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(ErrorStats::new(&[]), ErrorStats::default());
+    }
+}
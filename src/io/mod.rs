@@ -0,0 +1,159 @@
+//! Loaders of the maps and trajectories exported by the other tools.
+
+mod pcd;
+mod ply;
+mod trajectory;
+mod xyz;
+
+pub use self::trajectory::{Trajectory, TrajectoryFormat};
+
+use std::path::Path;
+
+use failure::{bail, Fallible};
+use nalgebra::Point3;
+
+/// Points with optional per-point colors, in `[0, 1]`.
+#[derive(Clone, Debug, Default)]
+pub struct PointCloud {
+    pub points: Vec<Point3<f32>>,
+    pub colors: Option<Vec<[f32; 3]>>,
+}
+
+impl PointCloud {
+    /// Loads a PLY (ASCII or binary), PCD or XYZ/CSV file, by its extension.
+    pub fn load<P: AsRef<Path>>(path: P) -> Fallible<Self> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("ply") => ply::parse(&data),
+            Some("pcd") => pcd::parse(&data),
+            Some("xyz") | Some("txt") | Some("csv") => xyz::parse(&data),
+            _ => bail!("unsupported point cloud file: {}", path.display()),
+        }
+    }
+}
+
+/// Splits the text header ending with the `end` line from the body.
+fn split_header<'a>(data: &'a [u8], end: &str) -> Fallible<(&'a str, &'a [u8])> {
+    let mut offset = 0;
+    for line in data.split(|&b| b == b'\n') {
+        offset += line.len() + 1;
+
+        if String::from_utf8_lossy(line).trim().starts_with(end) {
+            let header = std::str::from_utf8(&data[..offset.min(data.len())])?;
+            return Ok((header, &data[offset.min(data.len())..]));
+        }
+    }
+    bail!("missing `{}` in the header", end)
+}
+
+/// A reader of the numbers of an ASCII or binary body.
+enum BodyReader<'a> {
+    Ascii(std::str::SplitAsciiWhitespace<'a>),
+    Binary {
+        data: &'a [u8],
+        offset: usize,
+        big_endian: bool,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    /// The scale to bring a color channel into `[0, 1]`.
+    fn color_scale(self) -> f32 {
+        match self {
+            Self::U8 | Self::I8 => 1.0 / 255.0,
+            Self::U16 | Self::I16 => 1.0 / 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+impl<'a> BodyReader<'a> {
+    fn ascii(body: &'a [u8]) -> Fallible<Self> {
+        Ok(Self::Ascii(
+            std::str::from_utf8(body)?.split_ascii_whitespace(),
+        ))
+    }
+
+    fn binary(data: &'a [u8], big_endian: bool) -> Self {
+        Self::Binary {
+            data,
+            offset: 0,
+            big_endian,
+        }
+    }
+
+    fn read(&mut self, ty: ScalarType) -> Fallible<f64> {
+        match self {
+            Self::Ascii(tokens) => match tokens.next() {
+                Some(token) => Ok(token.parse()?),
+                None => bail!("unexpected end of file"),
+            },
+            Self::Binary {
+                data,
+                offset,
+                big_endian,
+            } => {
+                let size = ty.size();
+                if *offset + size > data.len() {
+                    bail!("unexpected end of file");
+                }
+                let value = read_binary(&data[*offset..*offset + size], ty, *big_endian);
+                *offset += size;
+                Ok(value)
+            }
+        }
+    }
+}
+
+fn read_binary(bytes: &[u8], ty: ScalarType, big_endian: bool) -> f64 {
+    macro_rules! read {
+        ($t:ty, $n:expr) => {{
+            let mut buf = [0u8; $n];
+            buf.copy_from_slice(bytes);
+            if big_endian {
+                <$t>::from_be_bytes(buf) as f64
+            } else {
+                <$t>::from_le_bytes(buf) as f64
+            }
+        }};
+    }
+
+    match ty {
+        ScalarType::I8 => bytes[0] as i8 as f64,
+        ScalarType::U8 => bytes[0] as f64,
+        ScalarType::I16 => read!(i16, 2),
+        ScalarType::U16 => read!(u16, 2),
+        ScalarType::I32 => read!(i32, 4),
+        ScalarType::U32 => read!(u32, 4),
+        ScalarType::F32 => read!(f32, 4),
+        ScalarType::F64 => read!(f64, 8),
+    }
+}
@@ -0,0 +1,372 @@
+//! https://pointclouds.org/documentation/tutorials/pcd_file_format.html
+
+use super::{split_header, BodyReader, PointCloud, ScalarType};
+
+use failure::{bail, Fallible};
+use nalgebra::Point3;
+
+struct Field {
+    name: String,
+    ty: ScalarType,
+    count: usize,
+}
+
+pub fn parse(data: &[u8]) -> Fallible<PointCloud> {
+    let (header, body) = split_header(data, "DATA")?;
+
+    let mut names = vec![];
+    let mut sizes = vec![];
+    let mut types = vec![];
+    let mut counts = vec![];
+    let mut points: Option<usize> = None;
+    let mut format = None;
+
+    for line in header.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let key = words.next();
+        let values: Vec<_> = words.collect();
+        match key {
+            Some("FIELDS") => names = values,
+            Some("SIZE") => sizes = values,
+            Some("TYPE") => types = values,
+            Some("COUNT") => counts = values,
+            Some("POINTS") => points = values.first().map(|v| v.parse()).transpose()?,
+            Some("DATA") => format = values.first().copied(),
+            _ => {}
+        }
+    }
+
+    if sizes.len() != names.len() || types.len() != names.len() {
+        bail!("mismatched PCD fields");
+    }
+
+    let fields = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            Ok(Field {
+                name: name.to_string(),
+                ty: parse_type(types[i], sizes[i])?,
+                count: counts.get(i).map(|c| c.parse()).transpose()?.unwrap_or(1),
+            })
+        })
+        .collect::<Fallible<Vec<_>>>()?;
+
+    let points = match points {
+        Some(points) => points,
+        None => bail!("missing the number of points"),
+    };
+
+    match format {
+        Some("ascii") => read_points(&mut BodyReader::ascii(body)?, &fields, points),
+        Some("binary") => read_points(&mut BodyReader::binary(body, false), &fields, points),
+        Some("binary_compressed") => {
+            let body = decompress(body, &fields, points)?;
+            read_points(&mut BodyReader::binary(&body, false), &fields, points)
+        }
+        _ => bail!("unknown PCD data format: {:?}", format),
+    }
+}
+
+fn parse_type(ty: &str, size: &str) -> Fallible<ScalarType> {
+    Ok(match (ty, size) {
+        ("I", "1") => ScalarType::I8,
+        ("U", "1") => ScalarType::U8,
+        ("I", "2") => ScalarType::I16,
+        ("U", "2") => ScalarType::U16,
+        ("I", "4") => ScalarType::I32,
+        ("U", "4") => ScalarType::U32,
+        ("F", "4") => ScalarType::F32,
+        ("F", "8") => ScalarType::F64,
+        _ => bail!("unknown PCD type: {} of size {}", ty, size),
+    })
+}
+
+fn read_points(reader: &mut BodyReader, fields: &[Field], points: usize) -> Fallible<PointCloud> {
+    let find = |name: &str| fields.iter().position(|f| f.name == name);
+
+    let (x, y, z) = match (find("x"), find("y"), find("z")) {
+        (Some(x), Some(y), Some(z)) => (x, y, z),
+        _ => bail!("missing the point positions"),
+    };
+    let rgb = find("rgb").or_else(|| find("rgba"));
+
+    // grown as the points are read, as the count of a corrupted header may be anything
+    let mut cloud = PointCloud {
+        points: vec![],
+        colors: rgb.map(|_| vec![]),
+    };
+
+    let mut row = vec![0.0; fields.len()];
+    let mut color = 0;
+    for _ in 0..points {
+        for (i, field) in fields.iter().enumerate() {
+            for c in 0..field.count {
+                if Some(i) == rgb && c == 0 {
+                    color = read_packed_color(reader, field.ty)?;
+                } else if c == 0 {
+                    row[i] = reader.read(field.ty)?;
+                } else {
+                    reader.read(field.ty)?;
+                }
+            }
+        }
+
+        let point = Point3::new(row[x] as f32, row[y] as f32, row[z] as f32);
+        // organized clouds mark the missing points with NaN
+        if !(point.x.is_finite() && point.y.is_finite() && point.z.is_finite()) {
+            continue;
+        }
+        cloud.points.push(point);
+
+        if let Some(colors) = &mut cloud.colors {
+            let channel = |shift: u32| ((color >> shift) & 0xff) as f32 / 255.0;
+            colors.push([channel(16), channel(8), channel(0)]);
+        }
+    }
+    Ok(cloud)
+}
+
+/// Reads the `0x00RRGGBB` bits of a color, which PCL usually packs into a float.
+fn read_packed_color(reader: &mut BodyReader, ty: ScalarType) -> Fallible<u32> {
+    let is_binary = matches!(reader, BodyReader::Binary { .. });
+
+    Ok(match ty {
+        // read the raw bits, since the float may not survive a conversion
+        ScalarType::F32 if is_binary => reader.read(ScalarType::U32)? as u32,
+        ScalarType::F32 | ScalarType::F64 => (reader.read(ty)? as f32).to_bits(),
+        _ => reader.read(ty)? as u32,
+    })
+}
+
+/// Decompresses a `binary_compressed` body, and turns its columns into rows.
+fn decompress(body: &[u8], fields: &[Field], points: usize) -> Fallible<Vec<u8>> {
+    if body.len() < 8 {
+        bail!("unexpected end of file");
+    }
+    let read_u32 = |bytes: &[u8]| {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(bytes);
+        u32::from_le_bytes(buf) as usize
+    };
+    let compressed_size = read_u32(&body[0..4]);
+    let uncompressed_size = read_u32(&body[4..8]);
+
+    let compressed = match body.get(8..8 + compressed_size) {
+        Some(compressed) => compressed,
+        None => bail!("unexpected end of file"),
+    };
+    let columns = lzf_decompress(compressed, uncompressed_size)?;
+
+    let field_sizes: Vec<_> = fields.iter().map(|f| f.ty.size() * f.count).collect();
+    let point_size: usize = field_sizes.iter().sum();
+    let size = match point_size.checked_mul(points) {
+        Some(size) if size <= columns.len() => size,
+        _ => bail!("unexpected end of file"),
+    };
+
+    let mut rows = vec![0u8; size];
+    let mut column_offset = 0;
+    let mut field_offset = 0;
+    for size in field_sizes {
+        for i in 0..points {
+            let src = column_offset + i * size;
+            let dst = i * point_size + field_offset;
+            rows[dst..dst + size].copy_from_slice(&columns[src..src + size]);
+        }
+        column_offset += size * points;
+        field_offset += size;
+    }
+    Ok(rows)
+}
+
+/// http://oldhome.schmorp.de/marc/liblzf.html
+fn lzf_decompress(input: &[u8], output_size: usize) -> Fallible<Vec<u8>> {
+    // a byte of the input is a byte of the output at most, but in the back references
+    let mut output = Vec::with_capacity(output_size.min(input.len()));
+    let mut bytes = input.iter().map(|&b| b as usize);
+    let mut next = || match bytes.next() {
+        Some(b) => Ok(b),
+        None => Err(failure::err_msg("corrupted LZF data")),
+    };
+
+    while output.len() < output_size {
+        let control = next()?;
+
+        if control < 32 {
+            // a literal run
+            for _ in 0..=control {
+                output.push(next()? as u8);
+            }
+        } else {
+            // a back reference
+            let mut len = control >> 5;
+            if len == 7 {
+                len += next()?;
+            }
+            len += 2;
+
+            let distance = ((control & 0x1f) << 8) + next()? + 1;
+            if distance > output.len() {
+                bail!("corrupted LZF data");
+            }
+
+            let start = output.len() - distance;
+            for i in 0..len {
+                output.push(output[start + i]);
+            }
+        }
+    }
+
+    if output.len() != output_size {
+        bail!("corrupted LZF data");
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POINTS: [[f32; 3]; 3] = [[1.0, 2.0, 3.0], [-1.0, 0.5, 0.0], [0.25, -4.0, 1e3]];
+    const COLORS: [u32; 3] = [0x00ff_0000, 0x0000_ff00, 0x0033_66ff];
+
+    fn header(ty: &str, format: &str, points: usize) -> String {
+        format!(
+            "# .PCD v0.7 - Point Cloud Data file format\nVERSION 0.7\nFIELDS x y z rgb\n\
+             SIZE 4 4 4 4\nTYPE F F F {}\nCOUNT 1 1 1 1\nWIDTH {}\nHEIGHT 1\n\
+             VIEWPOINT 0 0 0 1 0 0 0\nPOINTS {}\nDATA {}\n",
+            ty, points, points, format
+        )
+    }
+
+    /// The fields of the points, as PCL packs the colors into floats.
+    fn columns() -> Vec<[u32; 4]> {
+        POINTS
+            .iter()
+            .zip(&COLORS)
+            .map(|(p, &c)| [p[0].to_bits(), p[1].to_bits(), p[2].to_bits(), c])
+            .collect()
+    }
+
+    /// Compresses as literal runs only, which any LZF decoder reads.
+    fn lzf_literals(data: &[u8]) -> Vec<u8> {
+        data.chunks(32)
+            .flat_map(|chunk| {
+                let mut run = vec![chunk.len() as u8 - 1];
+                run.extend_from_slice(chunk);
+                run
+            })
+            .collect()
+    }
+
+    fn assert_cloud(cloud: &PointCloud) {
+        let points: Vec<_> = POINTS
+            .iter()
+            .map(|p| Point3::new(p[0], p[1], p[2]))
+            .collect();
+        assert_eq!(cloud.points, points);
+
+        let colors = cloud.colors.as_ref().unwrap();
+        let expected = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.2, 0.4, 1.0]];
+        assert_eq!(colors.len(), expected.len());
+        for (color, expected) in colors.iter().zip(&expected) {
+            for (c, e) in color.iter().zip(expected) {
+                assert!((c - e).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        let mut data = header("U", "ascii", POINTS.len());
+        for (p, c) in POINTS.iter().zip(&COLORS) {
+            data += &format!("{} {} {} {}\n", p[0], p[1], p[2], c);
+        }
+
+        assert_cloud(&parse(data.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn binary() {
+        let mut data = header("F", "binary", POINTS.len()).into_bytes();
+        for field in columns().iter().flatten() {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+
+        assert_cloud(&parse(&data).unwrap());
+    }
+
+    #[test]
+    fn binary_compressed() {
+        let columns = columns();
+        let mut uncompressed = vec![];
+        for i in 0..4 {
+            for point in &columns {
+                uncompressed.extend_from_slice(&point[i].to_le_bytes());
+            }
+        }
+        let compressed = lzf_literals(&uncompressed);
+
+        let mut data = header("F", "binary_compressed", POINTS.len()).into_bytes();
+        data.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        data.extend_from_slice(&(uncompressed.len() as u32).to_le_bytes());
+        data.extend_from_slice(&compressed);
+
+        assert_cloud(&parse(&data).unwrap());
+    }
+
+    #[test]
+    fn skips_the_missing_points() {
+        let data = "FIELDS x y z\nSIZE 4 4 4\nTYPE F F F\nPOINTS 3\nDATA ascii\n\
+                    1 2 3\nNaN NaN NaN\n4 5 6\n";
+        let cloud = parse(data.as_bytes()).unwrap();
+
+        assert_eq!(
+            cloud.points,
+            vec![Point3::new(1.0, 2.0, 3.0), Point3::new(4.0, 5.0, 6.0)]
+        );
+        assert!(cloud.colors.is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        let data = header("F", "binary", 2).into_bytes();
+        assert!(parse(&data).is_err());
+
+        // a corrupted count does not allocate the points ahead
+        let data = header("F", "binary", usize::MAX).into_bytes();
+        assert!(parse(&data).is_err());
+
+        let mut data = header("F", "binary_compressed", usize::MAX).into_bytes();
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&u32::MAX.to_le_bytes());
+        data.extend_from_slice(&[0, 0]);
+        assert!(parse(&data).is_err());
+    }
+
+    #[test]
+    fn lzf_back_references() {
+        // "ab", then 4 bytes from 2 bytes back
+        let input = [0x01, b'a', b'b', 0x40, 0x01];
+        assert_eq!(lzf_decompress(&input, 6).unwrap(), b"ababab");
+
+        // "a", then a long reference of 7 + 1 + 2 bytes from 1 byte back
+        let input = [0x00, b'a', 0xe0, 0x01, 0x00];
+        assert_eq!(lzf_decompress(&input, 11).unwrap(), vec![b'a'; 11]);
+    }
+
+    #[test]
+    fn lzf_corrupted() {
+        // a reference before the start
+        assert!(lzf_decompress(&[0x00, b'a', 0x20, 0x05], 4).is_err());
+        // a literal run past the end
+        assert!(lzf_decompress(&[0x03, b'a'], 4).is_err());
+        // more data than announced
+        assert!(lzf_decompress(&[0x01, b'a', b'b'], 1).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+//! http://paulbourke.net/dataformats/ply/
+
+use super::{split_header, BodyReader, PointCloud, ScalarType};
+
+use failure::{bail, Fallible};
+use nalgebra::Point3;
+
+enum Property {
+    Scalar(String, ScalarType),
+    List(ScalarType, ScalarType),
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+pub fn parse(data: &[u8]) -> Fallible<PointCloud> {
+    let (header, body) = split_header(data, "end_header")?;
+
+    let mut lines = header.lines().map(str::trim);
+    if lines.next() != Some("ply") {
+        bail!("not a PLY file");
+    }
+
+    let mut reader = None;
+    let mut elements: Vec<Element> = vec![];
+
+    for line in lines {
+        let words: Vec<_> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["format", format, _] => {
+                reader = Some(match *format {
+                    "ascii" => BodyReader::ascii(body)?,
+                    "binary_little_endian" => BodyReader::binary(body, false),
+                    "binary_big_endian" => BodyReader::binary(body, true),
+                    _ => bail!("unknown PLY format: {}", format),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse()?,
+                properties: vec![],
+            }),
+            ["property", "list", count, item, _] => match elements.last_mut() {
+                Some(element) => element
+                    .properties
+                    .push(Property::List(parse_type(count)?, parse_type(item)?)),
+                None => bail!("a property without an element"),
+            },
+            ["property", ty, name] => match elements.last_mut() {
+                Some(element) => element
+                    .properties
+                    .push(Property::Scalar(name.to_string(), parse_type(ty)?)),
+                None => bail!("a property without an element"),
+            },
+            _ => {}
+        }
+    }
+
+    let mut reader = match reader {
+        Some(reader) => reader,
+        None => bail!("missing the PLY format"),
+    };
+
+    for element in &elements {
+        if element.name == "vertex" {
+            return read_vertices(&mut reader, element);
+        }
+
+        // skip the other elements, e.g. the faces
+        for _ in 0..element.count {
+            read_row(&mut reader, element)?;
+        }
+    }
+    bail!("missing the vertex element")
+}
+
+fn parse_type(ty: &str) -> Fallible<ScalarType> {
+    Ok(match ty {
+        "char" | "int8" => ScalarType::I8,
+        "uchar" | "uint8" => ScalarType::U8,
+        "short" | "int16" => ScalarType::I16,
+        "ushort" | "uint16" => ScalarType::U16,
+        "int" | "int32" => ScalarType::I32,
+        "uint" | "uint32" => ScalarType::U32,
+        "float" | "float32" => ScalarType::F32,
+        "double" | "float64" => ScalarType::F64,
+        _ => bail!("unknown PLY type: {}", ty),
+    })
+}
+
+/// Reads the scalar properties of a row, skipping the lists.
+fn read_row(reader: &mut BodyReader, element: &Element) -> Fallible<Vec<f64>> {
+    let mut row = Vec::with_capacity(element.properties.len());
+    for property in &element.properties {
+        match property {
+            Property::Scalar(_, ty) => row.push(reader.read(*ty)?),
+            Property::List(count, item) => {
+                let count = reader.read(*count)? as usize;
+                for _ in 0..count {
+                    reader.read(*item)?;
+                }
+                row.push(0.0);
+            }
+        }
+    }
+    Ok(row)
+}
+
+fn read_vertices(reader: &mut BodyReader, element: &Element) -> Fallible<PointCloud> {
+    let find = |names: &[&str]| {
+        element
+            .properties
+            .iter()
+            .enumerate()
+            .find_map(|(i, property)| match property {
+                Property::Scalar(name, ty) if names.contains(&name.as_str()) => Some((i, *ty)),
+                _ => None,
+            })
+    };
+
+    let (x, y, z) = match (find(&["x"]), find(&["y"]), find(&["z"])) {
+        (Some(x), Some(y), Some(z)) => (x.0, y.0, z.0),
+        _ => bail!("missing the vertex positions"),
+    };
+
+    let colors = match (
+        find(&["red", "r", "diffuse_red"]),
+        find(&["green", "g", "diffuse_green"]),
+        find(&["blue", "b", "diffuse_blue"]),
+    ) {
+        (Some(r), Some(g), Some(b)) => Some([r, g, b]),
+        _ => None,
+    };
+
+    // grown as the rows are read, as the count of a corrupted header may be anything
+    let mut cloud = PointCloud {
+        points: vec![],
+        colors: colors.map(|_| vec![]),
+    };
+
+    for _ in 0..element.count {
+        let row = read_row(reader, element)?;
+        cloud
+            .points
+            .push(Point3::new(row[x] as f32, row[y] as f32, row[z] as f32));
+
+        if let (Some(colors), Some(channels)) = (&mut cloud.colors, &colors) {
+            let channel = |(i, ty): (usize, ScalarType)| row[i] as f32 * ty.color_scale();
+            colors.push([
+                channel(channels[0]),
+                channel(channels[1]),
+                channel(channels[2]),
+            ]);
+        }
+    }
+    Ok(cloud)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POINTS: [[f32; 3]; 3] = [[1.0, 2.0, 3.0], [-1.0, 0.5, 0.0], [0.25, -4.0, 1e3]];
+    const COLORS: [[u8; 3]; 3] = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+
+    fn header(format: &str, count: usize) -> String {
+        format!(
+            "ply\nformat {} 1.0\ncomment made by hand\nelement vertex {}\n\
+             property float x\nproperty float y\nproperty float z\n\
+             property uchar red\nproperty uchar green\nproperty uchar blue\nend_header\n",
+            format, count
+        )
+    }
+
+    fn binary(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian {
+            "binary_big_endian"
+        } else {
+            "binary_little_endian"
+        };
+
+        let mut data = header(format, POINTS.len()).into_bytes();
+        for (point, color) in POINTS.iter().zip(&COLORS) {
+            for v in point {
+                if big_endian {
+                    data.extend_from_slice(&v.to_be_bytes());
+                } else {
+                    data.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+            data.extend_from_slice(color);
+        }
+        data
+    }
+
+    fn assert_cloud(cloud: &PointCloud) {
+        let points: Vec<_> = POINTS
+            .iter()
+            .map(|p| Point3::new(p[0], p[1], p[2]))
+            .collect();
+        assert_eq!(cloud.points, points);
+
+        let colors = cloud.colors.as_ref().unwrap();
+        assert_eq!(colors.len(), COLORS.len());
+        for (color, expected) in colors.iter().zip(&COLORS) {
+            for (c, e) in color.iter().zip(expected) {
+                assert!((c - *e as f32 / 255.0).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        let mut data = header("ascii", POINTS.len());
+        for (point, color) in POINTS.iter().zip(&COLORS) {
+            data += &format!(
+                "{} {} {} {} {} {}\n",
+                point[0], point[1], point[2], color[0], color[1], color[2]
+            );
+        }
+
+        assert_cloud(&parse(data.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn binary_little_endian() {
+        assert_cloud(&parse(&binary(false)).unwrap());
+    }
+
+    #[test]
+    fn binary_big_endian() {
+        assert_cloud(&parse(&binary(true)).unwrap());
+    }
+
+    #[test]
+    fn skips_the_elements_before_the_vertices() {
+        let data =
+            "ply\nformat ascii 1.0\nelement face 2\nproperty list uchar int vertex_indices\n\
+                    element vertex 1\nproperty double x\nproperty double y\nproperty double z\n\
+                    end_header\n3 0 1 2\n4 0 1 2 3\n1 2 3\n";
+        let cloud = parse(data.as_bytes()).unwrap();
+
+        assert_eq!(cloud.points, vec![Point3::new(1.0, 2.0, 3.0)]);
+        assert!(cloud.colors.is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        let mut data = binary(false);
+        data.truncate(data.len() - 1);
+        assert!(parse(&data).is_err());
+
+        // a corrupted count does not allocate the points ahead
+        let data = header("binary_little_endian", usize::MAX);
+        assert!(parse(data.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse(b"ply\nformat ascii 1.0\nend_header\n").is_err());
+        assert!(parse(b"pcd\nformat ascii 1.0\nend_header\n").is_err());
+        assert!(parse(b"ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\n").is_err());
+    }
+}
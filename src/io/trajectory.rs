@@ -0,0 +1,220 @@
+//! TUM RGB-D: https://vision.in.tum.de/data/datasets/rgbd-dataset/file_formats
+//! KITTI odometry: http://www.cvlibs.net/datasets/kitti/eval_odometry.php
+
+use std::path::Path;
+
+use failure::{bail, Fallible};
+use nalgebra::{Isometry3, Matrix3, Quaternion, Rotation3, Translation3, UnitQuaternion};
+
+/// Enough for the rounding of the files, which is undone in a few iterations.
+const MAX_ITERATIONS: usize = 100;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TrajectoryFormat {
+    /// `timestamp tx ty tz qx qy qz qw`
+    Tum,
+    /// The first 3 rows of a row-major 4x4 pose matrix.
+    Kitti,
+}
+
+/// Camera poses in the world, in the order of the file.
+#[derive(Clone, Debug, Default)]
+pub struct Trajectory {
+    /// The timestamps of the poses; KITTI files use the frame indices instead.
+    pub timestamps: Vec<f64>,
+    pub poses: Vec<Isometry3<f64>>,
+}
+
+impl Trajectory {
+    /// Loads a TUM or KITTI file, detecting its format by the number of columns.
+    pub fn load<P: AsRef<Path>>(path: P) -> Fallible<Self> {
+        let text = std::fs::read_to_string(path)?;
+
+        let columns = rows(&text).next().transpose()?.map(|(_, row)| row.len());
+        match columns {
+            Some(8) => Self::parse(&text, TrajectoryFormat::Tum),
+            Some(12) => Self::parse(&text, TrajectoryFormat::Kitti),
+            Some(n) => bail!("unknown trajectory format with {} columns", n),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_with_format<P: AsRef<Path>>(path: P, format: TrajectoryFormat) -> Fallible<Self> {
+        Self::parse(&std::fs::read_to_string(path)?, format)
+    }
+
+    pub fn parse(text: &str, format: TrajectoryFormat) -> Fallible<Self> {
+        let mut trajectory = Self::default();
+
+        for (index, row) in rows(text).enumerate() {
+            let (line, row) = row?;
+            let (timestamp, pose) = match (format, row.as_slice()) {
+                (TrajectoryFormat::Tum, &[t, tx, ty, tz, qx, qy, qz, qw]) => {
+                    let quaternion = Quaternion::new(qw, qx, qy, qz);
+                    let rotation = match UnitQuaternion::try_new(quaternion, f64::EPSILON) {
+                        Some(rotation) => rotation,
+                        None => bail!("invalid rotation at line {}: zero quaternion", line),
+                    };
+                    (
+                        t,
+                        Isometry3::from_parts(Translation3::new(tx, ty, tz), rotation),
+                    )
+                }
+                (
+                    TrajectoryFormat::Kitti,
+                    &[r00, r01, r02, tx, r10, r11, r12, ty, r20, r21, r22, tz],
+                ) => {
+                    let matrix = Matrix3::new(r00, r01, r02, r10, r11, r12, r20, r21, r22);
+                    let rotation = closest_rotation(&matrix);
+                    (
+                        index as f64,
+                        Isometry3::from_parts(Translation3::new(tx, ty, tz), rotation),
+                    )
+                }
+                _ => bail!("invalid {:?} pose at line {}", format, line),
+            };
+
+            trajectory.timestamps.push(timestamp);
+            trajectory.poses.push(pose);
+        }
+        Ok(trajectory)
+    }
+
+    pub fn len(&self) -> usize {
+        self.poses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.poses.is_empty()
+    }
+}
+
+/// The rotation closest to the matrix, as the rows of the files are rounded.
+fn closest_rotation(matrix: &Matrix3<f64>) -> UnitQuaternion<f64> {
+    // starting from the rounded rotation, as the iterations get stuck at a half turn from it
+    let guess = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(*matrix));
+    let guess = UnitQuaternion::new_normalize(guess.into_inner());
+
+    let rotation =
+        Rotation3::from_matrix_eps(matrix, 1e-12, MAX_ITERATIONS, guess.to_rotation_matrix());
+    UnitQuaternion::from_rotation_matrix(&rotation)
+}
+
+/// Parses the non-empty rows with their line numbers, skipping the comments.
+fn rows(text: &str) -> impl Iterator<Item = Fallible<(usize, Vec<f64>)>> + '_ {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            let row: Result<Vec<f64>, _> = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|v| !v.is_empty())
+                .map(str::parse)
+                .collect();
+
+            match row {
+                Ok(row) => Ok((number, row)),
+                Err(_) => bail!("invalid number at line {}: {}", number, line),
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nalgebra::Vector3;
+
+    #[test]
+    fn tum() {
+        let text = "# timestamp tx ty tz qx qy qz qw\n\
+                    1305031102.175304 1.0 2.0 3.0 0 0 0 1\n\
+                    \n\
+                    1305031102.211214 4.0 5.0 6.0 0 0 0.7071068 0.7071068\n";
+        let trajectory = Trajectory::parse(text, TrajectoryFormat::Tum).unwrap();
+
+        assert_eq!(
+            trajectory.timestamps,
+            vec![1305031102.175304, 1305031102.211214]
+        );
+        assert_eq!(trajectory.poses[0], Isometry3::translation(1.0, 2.0, 3.0));
+
+        let pose = trajectory.poses[1];
+        assert_eq!(pose.translation.vector, Vector3::new(4.0, 5.0, 6.0));
+        let x = pose.rotation * Vector3::x();
+        assert!((x - Vector3::y()).norm() < 1e-6);
+    }
+
+    #[test]
+    fn kitti() {
+        let text = "1 0 0 0 0 1 0 0 0 0 1 0\n\
+                    0.707107 0 0.707107 1.5 0 1 0 -0.5 -0.707107 0 0.707107 2.5\n";
+        let trajectory = Trajectory::parse(text, TrajectoryFormat::Kitti).unwrap();
+
+        assert_eq!(trajectory.timestamps, vec![0.0, 1.0]);
+        assert_eq!(trajectory.poses[0], Isometry3::identity());
+
+        let pose = trajectory.poses[1];
+        assert_eq!(pose.translation.vector, Vector3::new(1.5, -0.5, 2.5));
+        let expected =
+            UnitQuaternion::from_axis_angle(&Vector3::y_axis(), std::f64::consts::FRAC_PI_4);
+        assert!(pose.rotation.angle_to(&expected) < 1e-6);
+    }
+
+    #[test]
+    fn kitti_rounded_rotations() {
+        // a quarter turn and a half turn, rounded as in the files
+        let text = "0.0000001 -0.9999999 0 0 0.9999999 0.0000001 0 0 0 0 1.0000001 0\n\
+                    1 0 0 0 0 -0.9999998 0.0000002 0 0 -0.0000002 -0.9999998 0\n";
+        let trajectory = Trajectory::parse(text, TrajectoryFormat::Kitti).unwrap();
+
+        let expected = [
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), std::f64::consts::FRAC_PI_2),
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), std::f64::consts::PI),
+        ];
+        for (pose, expected) in trajectory.poses.iter().zip(&expected) {
+            // a unit quaternion, which the rounded matrix alone does not give
+            assert!((pose.rotation.quaternion().norm() - 1.0).abs() < 1e-12);
+            assert!(pose.rotation.angle_to(expected) < 1e-6);
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(Trajectory::parse("0 1 2 3 0 0 0\n", TrajectoryFormat::Tum).is_err());
+        assert!(Trajectory::parse("0 1 2 3 0 0 0 1\n", TrajectoryFormat::Kitti).is_err());
+        assert!(Trajectory::parse("0 1 2 3 0 0 0 one\n", TrajectoryFormat::Tum).is_err());
+
+        let trajectory = Trajectory::parse("# nothing\n", TrajectoryFormat::Tum).unwrap();
+        assert!(trajectory.is_empty());
+    }
+
+    #[test]
+    fn line_numbers() {
+        let text = "# timestamp tx ty tz qx qy qz qw\n\
+                    \n\
+                    0 1 2 3 0 0 0 1\n\
+                    \n\
+                    # a comment\n\
+                    1 1 2 3 0 0 1\n";
+        let error = Trajectory::parse(text, TrajectoryFormat::Tum).unwrap_err();
+        assert_eq!(error.to_string(), "invalid Tum pose at line 6");
+
+        let error = Trajectory::parse("\n0 1 2 3 0 0 0 x\n", TrajectoryFormat::Tum).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid number at line 2: 0 1 2 3 0 0 0 x"
+        );
+    }
+
+    #[test]
+    fn zero_quaternion() {
+        let text = "0 1 2 3 0 0 0 1\n1 1 2 3 0 0 0 0\n";
+        let error = Trajectory::parse(text, TrajectoryFormat::Tum).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid rotation at line 2: zero quaternion"
+        );
+    }
+}
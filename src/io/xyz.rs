@@ -0,0 +1,105 @@
+//! Rows of `x y z` or `x y z r g b`, separated by spaces, tabs or commas,
+//! after an optional line of column names.
+
+use super::PointCloud;
+
+use failure::{bail, Fallible};
+use nalgebra::Point3;
+
+pub fn parse(data: &[u8]) -> Fallible<PointCloud> {
+    let text = std::str::from_utf8(data)?;
+
+    let mut rows = vec![];
+    let mut has_header = false;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+
+        let row: Result<Vec<f32>, _> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|v| !v.is_empty())
+            .map(str::parse)
+            .collect();
+
+        match row {
+            Ok(row) if row.len() >= 3 => rows.push(row),
+            // e.g. the column names of a CSV file, once before the points
+            Err(_) if rows.is_empty() && !has_header => has_header = true,
+            _ => bail!("invalid point at line {}: {}", index + 1, line),
+        }
+    }
+
+    let points = rows
+        .iter()
+        .map(|row| Point3::new(row[0], row[1], row[2]))
+        .collect();
+
+    let colors = if !rows.is_empty() && rows.iter().all(|row| row.len() >= 6) {
+        let max = rows
+            .iter()
+            .flat_map(|row| row[3..6].iter().cloned())
+            .fold(0.0f32, f32::max);
+        let scale = if max > 1.0 { 1.0 / 255.0 } else { 1.0 };
+
+        Some(
+            rows.iter()
+                .map(|row| [row[3] * scale, row[4] * scale, row[5] * scale])
+                .collect(),
+        )
+    } else {
+        None
+    };
+
+    Ok(PointCloud { points, colors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let data = b"# a comment\n1 2 3\n\n4\t5\t6\n";
+        let cloud = parse(data).unwrap();
+
+        assert_eq!(
+            cloud.points,
+            vec![Point3::new(1.0, 2.0, 3.0), Point3::new(4.0, 5.0, 6.0)]
+        );
+        assert!(cloud.colors.is_none());
+    }
+
+    #[test]
+    fn csv_with_colors() {
+        let data = b"x,y,z,r,g,b\n1,2,3,255,0,0\n4,5,6,0,0,255\n";
+        let cloud = parse(data).unwrap();
+
+        assert_eq!(cloud.points.len(), 2);
+        assert_eq!(cloud.colors, Some(vec![[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]]));
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(parse(b"1 2 3\n4 5\n").is_err());
+        // a header is not numeric
+        assert!(parse(b"1 2\n1 2 3\n").is_err());
+    }
+
+    #[test]
+    fn one_header() {
+        let error = parse(b"x y z\n\nr g b\n1 2 3\n").unwrap_err();
+        assert_eq!(error.to_string(), "invalid point at line 3: r g b");
+
+        // not a point cloud at all
+        let error = parse(b"ply\nformat ascii 1.0\nelement vertex 1\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid point at line 2: format ascii 1.0"
+        );
+
+        let error = parse(b"1 2 3\n# x y z\n4 5 6\nx y z\n").unwrap_err();
+        assert_eq!(error.to_string(), "invalid point at line 4: x y z");
+    }
+}
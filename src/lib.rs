@@ -1,7 +1,19 @@
 mod engine;
+mod eval;
+mod io;
 mod pipes;
 mod viewer;
 mod window;
 
-pub use viewer::alloc_thread;
-pub use window::{CameraControllerConfig, CameraFrustum};
+pub use engine::{Engine, ViewerSender};
+pub use eval::{Alignment, Association, Comparison, ErrorStats, Similarity};
+pub use io::{PointCloud, Trajectory, TrajectoryFormat};
+pub use pipes::{Pick, PickKind};
+pub use viewer::{alloc_thread, Viewer};
+pub use window::models::{EdgeKind, KeyFrameEdge, KeyFrameGraph, ViewerMessage};
+pub use window::{
+    Action, CameraControllerConfig, CameraFrustum, CameraIntrinsics, CameraMode, CameraTracking,
+    Colormap, Convention, Input, InputMap, PointShape, PointStyle, Projection, ViewLayout,
+    ViewPreset, ViewportCamera,
+};
+pub use winit::event::{MouseButton, VirtualKeyCode};
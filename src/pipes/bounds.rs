@@ -0,0 +1,77 @@
+use nalgebra::{Point3, Vector3};
+use slam_cv::Number;
+
+/// An axis-aligned box around the drawn data, to frame it with the camera.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bounds {
+    pub min: Point3<f32>,
+    pub max: Point3<f32>,
+}
+
+impl Bounds {
+    /// Bounds the points, ignoring the `outliers` fraction at both ends of each axis.
+    ///
+    /// The non-finite points are skipped; `None` is returned if nothing is left.
+    pub fn from_points<N, I>(points: I, outliers: f32) -> Option<Self>
+    where
+        N: 'static + Number,
+        I: IntoIterator<Item = Point3<N>>,
+    {
+        let mut axes = [vec![], vec![], vec![]];
+        for point in points {
+            let point = point.map(|v| num::cast::<N, f32>(v).unwrap_or(std::f32::NAN));
+            if point.iter().all(|v| v.is_finite()) {
+                for (axis, &v) in axes.iter_mut().zip(point.iter()) {
+                    axis.push(v);
+                }
+            }
+        }
+
+        let len = axes[0].len();
+        if len == 0 {
+            return None;
+        }
+
+        let skip = ((len as f32 * outliers) as usize).min((len - 1) / 2);
+        let mut min = Point3::origin();
+        let mut max = Point3::origin();
+        for (i, axis) in axes.iter_mut().enumerate() {
+            axis.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            min[i] = axis[skip];
+            max[i] = axis[len - 1 - skip];
+        }
+
+        Some(Self { min, max })
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    /// Unites the bounds of several renderers, any of which may be empty.
+    pub fn union_all<I>(bounds: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Option<Self>>,
+    {
+        bounds
+            .into_iter()
+            .flatten()
+            .fold(None, |sum, b| Some(sum.map_or(b, |sum: Self| sum.union(b))))
+    }
+
+    pub fn center(&self) -> Point3<f32> {
+        nalgebra::center(&self.min, &self.max)
+    }
+
+    pub fn size(&self) -> Vector3<f32> {
+        self.max - self.min
+    }
+
+    /// The radius of the bounding sphere.
+    pub fn radius(&self) -> f32 {
+        self.size().norm() / 2.0
+    }
+}
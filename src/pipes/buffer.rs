@@ -1,13 +1,23 @@
+use core::ops::Range;
+
 use super::vertex::GpuVertex;
 
+/// The minimum number of elements allocated on the GPU.
+const MIN_CAPACITY: u64 = 64;
+
+/// A vector mirrored on the GPU.
+///
+/// Only the range modified since the last upload is written,
+/// and the buffer grows with amortized doubling.
 pub struct GpuVec<D>
 where
     D: bytemuck::Pod + bytemuck::Zeroable + GpuVertex,
 {
-    cpu_vec: Option<Vec<D>>,
+    cpu_vec: Vec<D>,
+    dirty: Option<Range<usize>>,
 
     gpu_buffer: Option<wgpu::Buffer>,
-    gpu_buffer_size: u64,
+    gpu_buffer_capacity: u64,
 
     usage: wgpu::BufferUsage,
 }
@@ -18,42 +28,281 @@ where
 {
     pub fn new(usage: wgpu::BufferUsage) -> Self {
         Self {
-            cpu_vec: None,
+            cpu_vec: vec![],
+            dirty: None,
 
             gpu_buffer: None,
-            gpu_buffer_size: 0,
+            gpu_buffer_capacity: 0,
+
+            usage: usage | wgpu::BufferUsage::COPY_DST,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cpu_vec.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cpu_vec.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[D] {
+        &self.cpu_vec
+    }
+
+    /// Replaces the contents, marking only the modified elements as dirty.
+    pub fn update(&mut self, vec: Vec<D>) {
+        let range = modified_range(&self.cpu_vec, &vec);
+        self.cpu_vec = vec;
+        self.mark_dirty(range);
+    }
+
+    pub fn push(&mut self, value: D) {
+        self.cpu_vec.push(value);
+
+        let len = self.cpu_vec.len();
+        self.mark_dirty(len - 1..len);
+    }
+
+    pub fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = D>,
+    {
+        let start = self.cpu_vec.len();
+        self.cpu_vec.extend(iter);
+        self.mark_dirty(start..self.cpu_vec.len());
+    }
+
+    pub fn set(&mut self, index: usize, value: D) {
+        self.cpu_vec[index] = value;
+        self.mark_dirty(index..index + 1);
+    }
+
+    /// Shrinks the vector; the GPU buffer is kept and only the draw count changes.
+    pub fn truncate(&mut self, len: usize) {
+        self.cpu_vec.truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
 
-            usage,
+    /// Writes the dirty range into the GPU buffer, reallocating it if needed.
+    pub fn upload(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let data_size = core::mem::size_of::<D>();
+        let len = self.cpu_vec.len() as u64;
+
+        if let Some(capacity) = grown_capacity(self.gpu_buffer_capacity, len) {
+            self.gpu_buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("gpu_vec"),
+                size: capacity * data_size as u64,
+                usage: self.usage,
+            }));
+            self.gpu_buffer_capacity = capacity;
+            self.dirty = Some(0..self.cpu_vec.len());
+        }
+
+        let dirty = self.take_dirty();
+        if let (Some(buffer), Some(dirty)) = (&self.gpu_buffer, dirty) {
+            let cpu_data = bytemuck::cast_slice(&self.cpu_vec[dirty.clone()]);
+            let offset = (dirty.start * data_size) as wgpu::BufferAddress;
+            queue.write_buffer(buffer, offset, cpu_data);
         }
     }
 
-    pub fn update(&mut self, device: &wgpu::Device, vec: Vec<D>) {
-        self.cpu_vec = Some(vec);
-        self.update_buffer(device);
+    /// The number of vertices drawn by `set_buffer`.
+    pub fn vertex_count(&self) -> u32 {
+        (D::weight() * self.cpu_vec.len() as u64) as u32
     }
 
     pub fn set_buffer<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
         if let Some(buffer) = &self.gpu_buffer {
+            if self.cpu_vec.is_empty() {
+                return;
+            }
+
             let data_size = core::mem::size_of::<D>() as u64;
-            let gpu_buffer_size = self.gpu_buffer_size * data_size;
-            let vec_len = (D::weight() * self.gpu_buffer_size) as u32;
+            let len = self.cpu_vec.len() as u64;
 
-            render_pass.set_vertex_buffer(0, buffer, 0, gpu_buffer_size);
-            render_pass.draw(0..vec_len, 0..1);
+            render_pass.set_vertex_buffer(0, buffer, 0, len * data_size);
+            render_pass.draw(0..self.vertex_count(), 0..1);
         }
     }
 
-    fn update_buffer(&mut self, device: &wgpu::Device) {
-        if let Some(cpu_vec) = &self.cpu_vec {
-            let size = cpu_vec.len() as u64;
-            if size > self.gpu_buffer_size {
-                let cpu_data = bytemuck::cast_slice(&cpu_vec);
-                self.gpu_buffer = Some(device.create_buffer_with_data(cpu_data, self.usage));
-                self.gpu_buffer_size = size;
-            } else {
-                // TODO: more efficient write
-                // FIXME: to be implemented
+    /// Draws `vertices` vertices per element, e.g. a quad per point.
+    pub fn set_instance_buffer<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        vertices: u32,
+    ) {
+        if let Some(buffer) = &self.gpu_buffer {
+            if self.cpu_vec.is_empty() {
+                return;
             }
+
+            let data_size = core::mem::size_of::<D>() as u64;
+            let len = self.cpu_vec.len() as u64;
+
+            render_pass.set_vertex_buffer(0, buffer, 0, len * data_size);
+            render_pass.draw(0..vertices, 0..len as u32);
+        }
+    }
+
+    fn mark_dirty(&mut self, range: Range<usize>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+            None => range,
+        });
+    }
+
+    /// The dirty range to write, without the elements truncated since.
+    fn take_dirty(&mut self) -> Option<Range<usize>> {
+        let dirty = self.dirty.take()?;
+        let end = dirty.end.min(self.cpu_vec.len());
+        if dirty.start < end {
+            Some(dirty.start..end)
+        } else {
+            None
         }
     }
 }
+
+/// The range of `new` which differs from `old`, up to the end of `new` if the lengths differ.
+fn modified_range<D>(old: &[D], new: &[D]) -> Range<usize>
+where
+    D: bytemuck::Pod,
+{
+    let common = old.len().min(new.len());
+
+    let is_modified = |&i: &usize| bytemuck::bytes_of(&old[i]) != bytemuck::bytes_of(&new[i]);
+
+    let start = (0..common).find(is_modified).unwrap_or(common);
+    let end = if old.len() == new.len() {
+        (start..common)
+            .rev()
+            .find(is_modified)
+            .map(|i| i + 1)
+            .unwrap_or(start)
+    } else {
+        new.len()
+    };
+    start..end
+}
+
+/// The capacity to reallocate for `len` elements, or `None` if `capacity` is enough.
+fn grown_capacity(capacity: u64, len: u64) -> Option<u64> {
+    if len > capacity {
+        Some(len.max(capacity * 2).max(MIN_CAPACITY))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Value(u32);
+
+    unsafe impl bytemuck::Pod for Value {}
+    unsafe impl bytemuck::Zeroable for Value {}
+
+    impl GpuVertex for Value {
+        fn weight() -> u64 {
+            2
+        }
+    }
+
+    fn values(values: &[u32]) -> Vec<Value> {
+        values.iter().cloned().map(Value).collect()
+    }
+
+    /// A vector of the values, as if uploaded.
+    fn uploaded(values: &[u32]) -> GpuVec<Value> {
+        let mut vec = GpuVec::new(wgpu::BufferUsage::VERTEX);
+        vec.update(self::values(values));
+        vec.take_dirty();
+        vec
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut vec = uploaded(&[1, 2, 3]);
+        vec.update(values(&[1, 2, 3]));
+        assert_eq!(vec.take_dirty(), None);
+
+        assert_eq!(modified_range(&values(&[]), &values(&[])), 0..0);
+    }
+
+    #[test]
+    fn appended() {
+        let mut vec = uploaded(&[1, 2, 3]);
+        vec.update(values(&[1, 2, 3, 4, 5]));
+        assert_eq!(vec.take_dirty(), Some(3..5));
+
+        vec.push(Value(6));
+        vec.extend(values(&[7, 8]));
+        assert_eq!(vec.take_dirty(), Some(5..8));
+        assert_eq!(vec.vertex_count(), 16);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut vec = uploaded(&[1, 2, 3, 4, 5]);
+        vec.update(values(&[1, 2, 3]));
+        assert_eq!(vec.take_dirty(), None);
+        assert_eq!(vec.len(), 3);
+        assert_eq!(vec.vertex_count(), 6);
+
+        // the edits past the new end are not written
+        vec.set(2, Value(9));
+        vec.truncate(2);
+        assert_eq!(vec.take_dirty(), None);
+
+        vec.clear();
+        assert_eq!(vec.vertex_count(), 0);
+    }
+
+    #[test]
+    fn edited_in_the_middle() {
+        let mut vec = uploaded(&[1, 2, 3, 4, 5]);
+        vec.update(values(&[1, 2, 9, 9, 5]));
+        assert_eq!(vec.take_dirty(), Some(2..4));
+
+        // the edits are merged, and cut by a later truncation
+        vec.set(1, Value(0));
+        vec.set(3, Value(0));
+        vec.truncate(3);
+        assert_eq!(vec.take_dirty(), Some(1..3));
+
+        // both a change and a new length
+        assert_eq!(
+            modified_range(&values(&[1, 2, 3]), &values(&[1, 0, 3, 4])),
+            1..4
+        );
+        assert_eq!(modified_range(&values(&[1, 2, 3]), &values(&[1, 0])), 1..2);
+    }
+
+    #[test]
+    fn capacity_doubles() {
+        assert_eq!(grown_capacity(0, 0), None);
+        assert_eq!(grown_capacity(0, 1), Some(MIN_CAPACITY));
+        assert_eq!(grown_capacity(MIN_CAPACITY, MIN_CAPACITY), None);
+        assert_eq!(
+            grown_capacity(MIN_CAPACITY, MIN_CAPACITY + 1),
+            Some(2 * MIN_CAPACITY)
+        );
+        assert_eq!(
+            grown_capacity(MIN_CAPACITY, 3 * MIN_CAPACITY),
+            Some(3 * MIN_CAPACITY)
+        );
+        // shrinking keeps the buffer
+        assert_eq!(grown_capacity(2 * MIN_CAPACITY, 1), None);
+    }
+}
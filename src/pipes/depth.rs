@@ -0,0 +1,65 @@
+pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+pub struct DepthTexture {
+    _texture: wgpu::Texture,
+    view: wgpu::TextureView,
+}
+
+impl DepthTexture {
+    pub fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("depth_texture"),
+            size: wgpu::Extent3d {
+                // a zero-sized texture is invalid, e.g. on a minimized window
+                width: width.max(1),
+                height: height.max(1),
+                depth: 1,
+            },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+        });
+        let view = texture.create_default_view();
+
+        Self {
+            _texture: texture,
+            view,
+        }
+    }
+
+    /// Clears the depth with `LoadOp::Clear`, or keeps it with `LoadOp::Load`.
+    pub fn attachment(
+        &self,
+        load_op: wgpu::LoadOp,
+    ) -> wgpu::RenderPassDepthStencilAttachmentDescriptor<'_> {
+        wgpu::RenderPassDepthStencilAttachmentDescriptor {
+            attachment: &self.view,
+            depth_load_op: load_op,
+            depth_store_op: wgpu::StoreOp::Store,
+            clear_depth: 1.0,
+            stencil_load_op: load_op,
+            stencil_store_op: wgpu::StoreOp::Store,
+            clear_stencil: 0,
+        }
+    }
+}
+
+/// Overlays (`depth_test == false`) are always drawn and do not occlude anything.
+pub fn depth_stencil_state(depth_test: bool) -> wgpu::DepthStencilStateDescriptor {
+    wgpu::DepthStencilStateDescriptor {
+        format: DEPTH_FORMAT,
+        depth_write_enabled: depth_test,
+        depth_compare: if depth_test {
+            wgpu::CompareFunction::Less
+        } else {
+            wgpu::CompareFunction::Always
+        },
+        stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+        stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+        stencil_read_mask: 0,
+        stencil_write_mask: 0,
+    }
+}
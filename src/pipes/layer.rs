@@ -0,0 +1,82 @@
+use super::PipelineRenderer;
+use crate::window::PointStyle;
+
+use failure::{bail, Fallible};
+
+/// A source drawn by a renderer, which can be shown, hidden and restyled at runtime.
+pub trait Layer {
+    fn name(&self) -> &str;
+
+    /// Renames the layer, e.g. to tell apart those of several models in a window.
+    fn set_name(&mut self, name: String);
+
+    /// The number of drawn elements, e.g. points or keyframes.
+    fn count(&self) -> usize;
+
+    fn is_visible(&self) -> bool;
+
+    fn set_visible(&mut self, visible: bool);
+
+    /// The color painting all the elements instead of their own, if any.
+    fn color(&self) -> Option<[f32; 3]>;
+
+    fn set_color(&mut self, color: Option<[f32; 3]>);
+
+    /// The color of the source, shown in the legend when no color is set.
+    fn source_color(&self) -> [f32; 3];
+
+    /// The style of the points, if the layer draws points.
+    fn point_style(&self) -> Option<PointStyle> {
+        None
+    }
+
+    fn set_point_style(&mut self, _style: PointStyle) {}
+
+    /// The scale of the depth of the frustums, if the layer draws keyframes.
+    fn frustum_scale(&self) -> Option<f32> {
+        None
+    }
+
+    fn set_frustum_scale(&mut self, _scale: f32) {}
+}
+
+/// A change of the layers of a name, e.g. requested by the application.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayerChange {
+    Visible(bool),
+    Color(Option<[f32; 3]>),
+    FrustumScale(f32),
+}
+
+impl LayerChange {
+    /// Applies the change to every layer named `name`, returning whether any was found.
+    pub fn apply(&self, layers: Vec<&mut dyn Layer>, name: &str) -> bool {
+        let mut found = false;
+        for layer in layers.into_iter().filter(|l| l.name() == name) {
+            match *self {
+                Self::Visible(visible) => layer.set_visible(visible),
+                Self::Color(color) => layer.set_color(color),
+                Self::FrustumScale(scale) => layer.set_frustum_scale(scale),
+            }
+            found = true;
+        }
+        found
+    }
+
+    /// Applies the changes by name, failing with the names matching no layer.
+    pub fn apply_all(
+        changes: &[(String, Self)],
+        renderer: &mut dyn PipelineRenderer,
+    ) -> Fallible<()> {
+        let unknown: Vec<_> = changes
+            .iter()
+            .filter(|(name, change)| !change.apply(renderer.layers(), name))
+            .map(|(name, _)| name.as_str())
+            .collect();
+
+        if !unknown.is_empty() {
+            bail!("no layer named {}", unknown.join(", "));
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,21 @@
+mod bounds;
 mod buffer;
 mod builder;
+mod depth;
+mod layer;
 mod module;
+mod pick;
 mod renderer;
+mod transform;
 mod vertex;
 
+pub use self::bounds::Bounds;
 pub use self::buffer::GpuVec;
 pub use self::builder::{PipelineBuilder, PipelineDataBuilder};
+pub use self::depth::{depth_stencil_state, DepthTexture};
+pub use self::layer::{Layer, LayerChange};
 pub use self::module::StaticShaderModule;
+pub use self::pick::{Pick, PickIndex, PickKind, PickRay};
 pub use self::renderer::PipelineRenderer;
+pub use self::transform::cast_isometry;
 pub use self::vertex::{GpuVertex, VertexFormat};
@@ -0,0 +1,324 @@
+use nalgebra::{Point3, Unit, Vector3};
+use slam_cv::Number;
+
+/// The number of points in a leaf of the `PickIndex`.
+const LEAF_SIZE: usize = 16;
+
+/// A ray from the camera through the cursor, widened by a tolerance in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PickRay {
+    pub origin: Point3<f32>,
+    pub direction: Unit<Vector3<f32>>,
+    /// The tolerance at the origin, e.g. of an orthographic camera.
+    pub radius: f32,
+    /// The growth of the tolerance with the distance, e.g. of a perspective camera.
+    pub spread: f32,
+}
+
+impl PickRay {
+    /// The tolerance at the distance `depth` along the ray.
+    pub fn tolerance(&self, depth: f32) -> f32 {
+        self.radius + self.spread * depth.max(0.0)
+    }
+
+    /// The distance of the point along the ray, if it is within the tolerance.
+    pub fn hit(&self, point: &Point3<f32>) -> Option<f32> {
+        let (depth, distance) = self.project(point);
+
+        if depth >= 0.0 && distance <= self.tolerance(depth) {
+            Some(depth)
+        } else {
+            None
+        }
+    }
+
+    /// The distance of the point along the ray, and from the ray.
+    fn project(&self, point: &Point3<f32>) -> (f32, f32) {
+        let offset = point - self.origin;
+        let depth = offset.dot(&self.direction);
+        let distance = (offset - self.direction.into_inner() * depth).norm();
+        (depth, distance)
+    }
+}
+
+/// What kind of data is picked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PickKind {
+    Landmark,
+    KeyFrame,
+}
+
+/// The data under the cursor.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pick {
+    pub kind: PickKind,
+    /// The index in the collection of the source, e.g. of `PointSource::collect_visual_points`.
+    pub index: usize,
+    /// The index of the model among those drawn in the window, in the order they were added.
+    pub model: usize,
+    pub position: Point3<f32>,
+    /// The distance along the ray.
+    pub depth: f32,
+}
+
+impl Pick {
+    /// The nearest pick of several renderers, any of which may have missed.
+    pub fn nearest<I>(picks: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Option<Self>>,
+    {
+        picks
+            .into_iter()
+            .flatten()
+            .min_by(|a, b| a.depth.partial_cmp(&b.depth).unwrap())
+    }
+}
+
+enum Node {
+    Leaf { start: usize, end: usize },
+    Branch { left: usize, right: usize },
+}
+
+/// A bounding sphere.
+struct Sphere {
+    center: Point3<f32>,
+    radius: f32,
+}
+
+/// A tree of bounding spheres over points, to find the nearest one along a ray.
+pub struct PickIndex {
+    kind: PickKind,
+
+    points: Vec<(usize, Point3<f32>)>,
+    nodes: Vec<(Sphere, Node)>,
+}
+
+impl PickIndex {
+    /// Indexes the points by their order; the non-finite points are skipped.
+    pub fn new<N, I>(kind: PickKind, points: I) -> Self
+    where
+        N: 'static + Number,
+        I: IntoIterator<Item = Point3<N>>,
+    {
+        let mut points: Vec<_> = points
+            .into_iter()
+            .map(|p| p.map(|v| num::cast::<N, f32>(v).unwrap_or(std::f32::NAN)))
+            .enumerate()
+            .filter(|(_, p)| p.iter().all(|v| v.is_finite()))
+            .collect();
+
+        let mut nodes = vec![];
+        if !points.is_empty() {
+            let len = points.len();
+            build(&mut nodes, &mut points, 0, len);
+        }
+
+        Self {
+            kind,
+            points,
+            nodes,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// The nearest point along the ray, within its tolerance.
+    pub fn pick(&self, ray: &PickRay) -> Option<Pick> {
+        let mut best: Option<(f32, usize)> = None;
+
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+        while let Some(node) = stack.pop() {
+            let (sphere, node) = &self.nodes[node];
+
+            let (depth, distance) = ray.project(&sphere.center);
+            let nearest = depth - sphere.radius;
+            let farthest = depth + sphere.radius;
+            if farthest < 0.0
+                || distance - sphere.radius > ray.tolerance(farthest)
+                || best.map_or(false, |(best, _)| nearest >= best)
+            {
+                continue;
+            }
+
+            match *node {
+                Node::Leaf { start, end } => {
+                    for (i, (_, point)) in self.points[start..end].iter().enumerate() {
+                        if let Some(depth) = ray.hit(point) {
+                            if best.map_or(true, |(best, _)| depth < best) {
+                                best = Some((depth, start + i));
+                            }
+                        }
+                    }
+                }
+                Node::Branch { left, right } => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+            }
+        }
+
+        best.map(|(depth, i)| {
+            let (index, position) = self.points[i];
+            Pick {
+                kind: self.kind,
+                index,
+                model: 0,
+                position,
+                depth,
+            }
+        })
+    }
+}
+
+/// Adds the node of `points[start..end]` and its children, returning its index.
+fn build(
+    nodes: &mut Vec<(Sphere, Node)>,
+    points: &mut [(usize, Point3<f32>)],
+    start: usize,
+    end: usize,
+) -> usize {
+    let slice = &mut points[start..end];
+
+    let (min, max) = slice
+        .iter()
+        .fold((slice[0].1, slice[0].1), |(min, max), (_, p)| {
+            (min.inf(p), max.sup(p))
+        });
+    let center = nalgebra::center(&min, &max);
+    let radius = slice
+        .iter()
+        .map(|(_, p)| (p - center).norm())
+        .fold(0.0, f32::max);
+    let sphere = Sphere { center, radius };
+
+    let index = nodes.len();
+    if end - start <= LEAF_SIZE {
+        nodes.push((sphere, Node::Leaf { start, end }));
+        return index;
+    }
+
+    // split at the median of the longest axis
+    let axis = (max - min).imax();
+    slice.sort_by(|(_, a), (_, b)| a[axis].partial_cmp(&b[axis]).unwrap());
+
+    nodes.push((sphere, Node::Leaf { start, end }));
+    let middle = start + (end - start) / 2;
+    let left = build(nodes, points, start, middle);
+    let right = build(nodes, points, middle, end);
+    nodes[index].1 = Node::Branch { left, right };
+
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// A ray along `-z` from `(0, 0, 10)`.
+    fn ray(radius: f32, spread: f32) -> PickRay {
+        PickRay {
+            origin: Point3::new(0.0, 0.0, 10.0),
+            direction: -Vector3::z_axis(),
+            radius,
+            spread,
+        }
+    }
+
+    #[test]
+    fn nearest_along_the_ray() {
+        let points = vec![
+            Point3::new(0.0, 0.0, -5.0),
+            Point3::new(0.05, 0.0, 2.0),
+            Point3::new(0.0, 0.0, 20.0), // behind the origin
+            Point3::new(1.0, 0.0, 8.0),  // beside the ray
+        ];
+        let index = PickIndex::new(PickKind::Landmark, points);
+
+        let pick = index.pick(&ray(0.1, 0.0)).unwrap();
+        assert_eq!(pick.kind, PickKind::Landmark);
+        assert_eq!(pick.index, 1);
+        assert_eq!(pick.position, Point3::new(0.05, 0.0, 2.0));
+        assert!((pick.depth - 8.0).abs() < 1e-5);
+
+        assert_eq!(index.pick(&ray(0.01, 0.0)).unwrap().index, 0);
+    }
+
+    #[test]
+    fn tolerance_grows_with_depth() {
+        let ray = ray(0.0, 0.1);
+        assert!(ray.hit(&Point3::new(0.5, 0.0, 0.0)).is_some());
+        assert!(ray.hit(&Point3::new(0.5, 0.0, 8.0)).is_none());
+
+        // only the far one is within the tolerance
+        let points = vec![Point3::new(0.5, 0.0, 8.0), Point3::new(0.5, 0.0, 0.0)];
+        let pick = PickIndex::new(PickKind::KeyFrame, points)
+            .pick(&ray)
+            .unwrap();
+        assert_eq!(pick.index, 1);
+    }
+
+    #[test]
+    fn skips_non_finite_points() {
+        let points = vec![
+            Point3::new(std::f64::NAN, 0.0, 0.0),
+            Point3::new(0.0, std::f64::INFINITY, 0.0),
+            Point3::new(0.0, 0.0, 0.0),
+        ];
+        let index = PickIndex::new(PickKind::Landmark, points);
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.pick(&ray(0.1, 0.0)).unwrap().index, 2);
+
+        let empty = PickIndex::new(PickKind::Landmark, Vec::<Point3<f32>>::new());
+        assert!(empty.is_empty());
+        assert!(empty.pick(&ray(0.1, 0.0)).is_none());
+    }
+
+    #[test]
+    fn same_as_brute_force() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut point = || {
+            Point3::new(
+                rng.gen_range(-5.0, 5.0),
+                rng.gen_range(-5.0, 5.0),
+                rng.gen_range(-5.0, 5.0),
+            )
+        };
+
+        let points: Vec<Point3<f32>> = (0..LEAF_SIZE * 40).map(|_| point()).collect();
+        let index = PickIndex::new(PickKind::Landmark, points.clone());
+
+        let mut hits = 0;
+        for _ in 0..200 {
+            let origin = point() * 3.0;
+            let ray = PickRay {
+                origin,
+                direction: Unit::new_normalize(point() - origin),
+                radius: 0.05,
+                spread: 0.01,
+            };
+
+            let expected = points
+                .iter()
+                .enumerate()
+                .filter_map(|(i, p)| ray.hit(p).map(|depth| (i, depth)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            let pick = index.pick(&ray).map(|pick| (pick.index, pick.depth));
+            assert_eq!(pick, expected);
+
+            hits += pick.is_some() as usize;
+        }
+        assert!(hits > 50);
+    }
+}
@@ -1,3 +1,59 @@
+use super::bounds::Bounds;
+use super::layer::Layer;
+use super::pick::{Pick, PickRay};
+use crate::window::KeyFrameView;
+
+use nalgebra::Isometry3;
+
 pub trait PipelineRenderer {
-    fn render<'a>(&'a mut self, device: &wgpu::Device, render_pass: &mut wgpu::RenderPass<'a>);
+    /// Pulls the pending updates of the sources, if any.
+    fn sync(&mut self) {}
+
+    /// The bounds of the data drawn so far, to frame it with the camera.
+    fn bounds(&self) -> Option<Bounds> {
+        None
+    }
+
+    /// The most recent keyframe drawn, which the camera can track.
+    fn latest_keyframe(&self) -> Option<KeyFrameView> {
+        None
+    }
+
+    /// The sources drawn, to list and toggle them in the overlay.
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        vec![]
+    }
+
+    /// Moves all the data drawn, e.g. to place a model in a scene with the others.
+    fn set_transform(&mut self, _transform: &Isometry3<f32>) {}
+
+    /// The nearest data along the ray, e.g. under the cursor.
+    fn pick(&mut self, _ray: &PickRay) -> Option<Pick> {
+        None
+    }
+
+    /// Collects and uploads the data changed since the last frame,
+    /// once before the frame is drawn in every viewport.
+    fn prepare(&mut self, _device: &wgpu::Device, _queue: &wgpu::Queue) {}
+
+    /// Draws the prepared data tested against the depth of the others, once for each viewport.
+    fn render<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        _render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+    }
+
+    /// Draws the prepared data ignoring the depth of the others, e.g. the trajectory.
+    ///
+    /// This is called after `render` of every pipeline of the render pass,
+    /// so that nothing depth-tested paints over it.
+    fn render_overlay<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        _render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+    }
 }
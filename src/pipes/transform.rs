@@ -0,0 +1,16 @@
+use nalgebra::{Isometry3, Quaternion, UnitQuaternion};
+use slam_cv::Number;
+
+/// Casts a transform of the application into the numbers of a renderer.
+pub fn cast_isometry<N>(iso: &Isometry3<f32>) -> Isometry3<N>
+where
+    N: 'static + Number,
+{
+    let cast = |v: f32| N::from(v).unwrap();
+
+    let rotation = iso.rotation.coords.map(cast);
+    Isometry3::from_parts(
+        iso.translation.vector.map(cast).into(),
+        UnitQuaternion::new_normalize(Quaternion::from(rotation)),
+    )
+}
@@ -1,10 +1,21 @@
+use std::path::Path;
+use std::sync::mpsc;
+
 use crate::{
-    engine::{Engine, EngineBuilder},
-    pipes::{PipelineBuilder, PipelineDataBuilder, VertexFormat},
-    window::{models, IsometrySource, LineSource, PointSource, WindowBuilder},
+    engine::{Engine, EngineBuilder, ViewerSender},
+    eval::Comparison,
+    io::{PointCloud, Trajectory},
+    pipes::{LayerChange, Pick, PipelineBuilder, PipelineDataBuilder, VertexFormat},
+    window::{
+        models, Action, CameraMode, Convention, HeadlessWindow, Input, IsometrySource, LineSource,
+        PointSource, ViewLayout, WindowBuilder, WindowCommand,
+    },
 };
 
-use nalgebra::{allocator::Allocator, DefaultAllocator, DimName, Point, Point3};
+use failure::Fallible;
+use nalgebra::{
+    allocator::Allocator, DefaultAllocator, DimName, Isometry3, Point, Point3, Unit, Vector3, U3,
+};
 use slam_cv::prelude::*;
 
 /// **caution**: This function can only be called once per process.
@@ -13,7 +24,15 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
-    Viewer { windows: vec![] }
+    let (commands, command_receiver) = mpsc::channel();
+
+    Viewer {
+        windows: vec![],
+        same_window: false,
+        senders: vec![],
+        commands,
+        command_receiver,
+    }
 }
 
 pub struct Viewer<N>
@@ -21,7 +40,12 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
-    windows: Vec<(WindowBuilder<N>, Box<dyn PipelineBuilder<N>>)>,
+    windows: Vec<(WindowBuilder<N>, models::SceneModel<N>)>,
+    /// Whether the next model joins the last window.
+    same_window: bool,
+    senders: Vec<mpsc::Sender<models::ViewerMessage<N>>>,
+    commands: mpsc::Sender<WindowCommand>,
+    command_receiver: mpsc::Receiver<WindowCommand>,
 }
 
 impl<N> Viewer<N>
@@ -40,6 +64,34 @@ where
         self.add(models::WorldModel::new(world))
     }
 
+    /// Adds a world whose keyframes show their images, in the order of the keyframes.
+    pub fn add_world_with_images<F, KF, W>(
+        self,
+        world: W,
+        images: Vec<Option<image::RgbaImage>>,
+    ) -> Self
+    where
+        F: 'static + Landmark<Number = N> + Clone,
+        KF: 'static + KeyFrame<Number = N, Feature = F> + Clone,
+        W: 'static + World<Number = N, KeyFrame = KF, Landmark = F> + Clone,
+        models::WorldModel<N, F, KF, W>:
+            PipelineDataBuilder<N> + PointSource<N> + LineSource<N> + IsometrySource<N>,
+    {
+        self.add(models::WorldModel::with_images(world, images))
+    }
+
+    /// Adds a world with the covisibility, spanning tree and loop closure edges of its keyframes.
+    pub fn add_world_with_graph<F, KF, W>(self, world: W, graph: models::KeyFrameGraph) -> Self
+    where
+        F: 'static + Landmark<Number = N> + Clone,
+        KF: 'static + KeyFrame<Number = N, Feature = F> + Clone,
+        W: 'static + World<Number = N, KeyFrame = KF, Landmark = F> + Clone,
+        models::WorldModel<N, F, KF, W>:
+            PipelineDataBuilder<N> + PointSource<N> + LineSource<N> + IsometrySource<N>,
+    {
+        self.add(models::WorldModel::new(world).with_graph(graph))
+    }
+
     pub fn add_points<D>(self, points: Vec<Point<N, D>>) -> Self
     where
         D: DimName,
@@ -49,6 +101,214 @@ where
         self.add(models::PointsModel::new(points))
     }
 
+    /// Panics if `colors` has not the same length as `points`.
+    pub fn add_colored_points<D>(self, points: Vec<Point<N, D>>, colors: Vec<[f32; 3]>) -> Self
+    where
+        D: DimName,
+        DefaultAllocator: Allocator<N, D>,
+        models::PointsModel<N, D>: PipelineBuilder<N> + PipelineDataBuilder<N> + PointSource<f32>,
+    {
+        self.add(models::PointsModel::with_colors(points, colors))
+    }
+
+    pub fn add_point_cloud(self, cloud: PointCloud) -> Self
+    where
+        models::PointsModel<f32, U3>: PipelineBuilder<N> + PipelineDataBuilder<N>,
+    {
+        match cloud.colors {
+            Some(colors) => self.add(models::PointsModel::with_colors(cloud.points, colors)),
+            None => self.add(models::PointsModel::new(cloud.points)),
+        }
+    }
+
+    /// Loads a PLY, PCD or XYZ file, see [`PointCloud::load`].
+    pub fn add_point_cloud_file<P>(self, path: P) -> Fallible<Self>
+    where
+        P: AsRef<Path>,
+        models::PointsModel<f32, U3>: PipelineBuilder<N> + PipelineDataBuilder<N>,
+    {
+        Ok(self.add_point_cloud(PointCloud::load(path)?))
+    }
+
+    pub fn add_trajectory(self, trajectory: &Trajectory) -> Self
+    where
+        models::TrajectoryModel: PipelineBuilder<N> + PipelineDataBuilder<N>,
+    {
+        let poses = trajectory
+            .poses
+            .iter()
+            .map(|p| nalgebra::convert(*p))
+            .collect();
+        self.add(models::TrajectoryModel::new(poses))
+    }
+
+    /// Loads a TUM or KITTI file, see [`Trajectory::load`].
+    pub fn add_trajectory_file<P>(self, path: P) -> Fallible<Self>
+    where
+        P: AsRef<Path>,
+        models::TrajectoryModel: PipelineBuilder<N> + PipelineDataBuilder<N>,
+    {
+        Ok(self.add_trajectory(&Trajectory::load(path)?))
+    }
+
+    /// Adds a window with both trajectories of a [`Comparison`] and their errors,
+    /// as the `"Ground truth"`, `"Estimate"` and `"Errors"` layers.
+    pub fn add_comparison(self, comparison: &Comparison) -> Self
+    where
+        models::ComparisonModel: PipelineBuilder<N> + PipelineDataBuilder<N>,
+    {
+        self.add(models::ComparisonModel::new(comparison))
+    }
+
+    /// Adds a window which is updated by the messages of [`Viewer::sender`].
+    pub fn add_live(mut self) -> Self
+    where
+        models::LiveModel<N>: PipelineBuilder<N> + PipelineDataBuilder<N>,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.senders.push(sender);
+        self.add(models::LiveModel::new(receiver))
+    }
+
+    /// Adds the next model into the last added window, drawn together with its models,
+    /// instead of opening a new window.
+    pub fn same_window(mut self) -> Self {
+        self.same_window = true;
+        self
+    }
+
+    /// Moves the last added model, e.g. to align a ground truth with the map in the same window.
+    pub fn with_transform(mut self, transform: Isometry3<f32>) -> Self {
+        if let Some((_, scene)) = self.windows.last_mut() {
+            scene.set_transform(transform);
+        }
+        self
+    }
+
+    /// Prefixes the names of the layers of the last added model, e.g. `"gt"` for `"gt/Trajectory"`,
+    /// to tell them apart from those of the other models in the same window.
+    pub fn with_layer_prefix(mut self, prefix: &str) -> Self {
+        if let Some((_, scene)) = self.windows.last_mut() {
+            scene.set_prefix(prefix.to_string());
+        }
+        self
+    }
+
+    /// Sets the camera mode of the last added window.
+    pub fn with_camera_mode(mut self, mode: CameraMode) -> Self {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.camera_controller.mode = mode;
+        }
+        self
+    }
+
+    /// Splits the last added window into viewports, e.g. `ViewLayout::QUAD`.
+    ///
+    /// The screenshots and recordings show all the viewports; `render_offscreen` ignores them.
+    pub fn with_layout(mut self, layout: ViewLayout) -> Self {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.layout = layout;
+        }
+        self
+    }
+
+    /// Moves the cameras of all the viewports of the last added window together.
+    pub fn with_linked_cameras(mut self) -> Self {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.link_cameras = true;
+        }
+        self
+    }
+
+    /// Sets the up axis of the last added window, e.g. `Vector3::z_axis()`.
+    pub fn with_up_axis(mut self, up_axis: Unit<Vector3<N>>) -> Self {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.camera.set_up_axis(up_axis);
+        }
+        self
+    }
+
+    /// Sets the up axis of the last added window by the convention of its data.
+    pub fn with_convention(self, convention: Convention) -> Self {
+        self.with_up_axis(convention.up_axis())
+    }
+
+    /// Binds a key or a mouse button of the last added window to the action.
+    pub fn bind(mut self, input: impl Into<Input>, action: Action) -> Self {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.input_map.bind(input, action);
+        }
+        self
+    }
+
+    /// Calls the callback when a key or a mouse button of the last added window is pressed.
+    pub fn bind_callback<F>(mut self, input: impl Into<Input>, callback: F) -> Self
+    where
+        F: 'static + FnMut() + Send,
+    {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.input_map.bind_callback(input, callback);
+        }
+        self
+    }
+
+    /// Calls the callback with the landmark or the keyframe clicked in the last added window.
+    pub fn on_pick<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(&Pick) + Send,
+    {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.input_map.bind_pick(callback);
+        }
+        self
+    }
+
+    /// Calls the callback with the failures of the last added window,
+    /// e.g. to save a screenshot or a recorded frame.
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: 'static + FnMut(&failure::Error) + Send,
+    {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.error_callback = Some(Box::new(callback));
+        }
+        self
+    }
+
+    /// Hides the layers of the name in the last added window, e.g. `"Landmarks"`.
+    ///
+    /// A name matching no layer is told to `on_error`, or fails `render_offscreen`.
+    pub fn hide_layer(self, name: &str) -> Self {
+        self.change_layer(name, LayerChange::Visible(false))
+    }
+
+    /// Paints the layers of the name in the last added window with the color,
+    /// instead of the colors of their source.
+    pub fn with_layer_color(self, name: &str, color: [f32; 3]) -> Self {
+        self.change_layer(name, LayerChange::Color(Some(color)))
+    }
+
+    /// Scales the depth of the frustums of the layers of the name in the last added window.
+    pub fn with_frustum_scale(self, name: &str, scale: f32) -> Self {
+        self.change_layer(name, LayerChange::FrustumScale(scale))
+    }
+
+    fn change_layer(mut self, name: &str, change: LayerChange) -> Self {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.layers.push((name.to_string(), change));
+        }
+        self
+    }
+
+    /// Removes the binding of a key or a mouse button of the last added window,
+    /// e.g. `VirtualKeyCode::Escape` to keep it open.
+    pub fn unbind(mut self, input: impl Into<Input>) -> Self {
+        if let Some((window, _)) = self.windows.last_mut() {
+            window.input_map.unbind(input);
+        }
+        self
+    }
+
     #[cfg(feature = "rust-cv")]
     pub fn add_matches<D>(self, matches: Vec<cv_core::FeatureMatch<Point<N, D>>>) -> Self
     where
@@ -77,22 +337,61 @@ where
     where
         P: 'static + PipelineBuilder<N>,
     {
-        self.windows.push((window, Box::new(pipe)));
+        let pipe = Box::new(pipe);
+        match self.windows.last_mut() {
+            Some((_, scene)) if self.same_window => scene.add(pipe),
+            _ => self.windows.push((window, models::SceneModel::new(pipe))),
+        }
+        self.same_window = false;
         self
     }
 
+    /// Returns a handle to push updates into the live windows, and to capture the frames.
+    pub fn sender(&self) -> ViewerSender<N> {
+        ViewerSender::new(self.senders.clone(), self.commands.clone())
+    }
+
     pub fn run(self) {
         self.compile().run()
     }
 
     /// TODO: cross-platform compatibility
-    pub fn spawn(self) -> Engine {
-        self.compile().spawn()
+    pub fn spawn(self) -> (Engine, ViewerSender<N>) {
+        let sender = self.sender();
+        (self.compile().spawn(), sender)
+    }
+
+    /// Renders every window once into an image, with its initial camera framing the data.
+    ///
+    /// No display is needed, so this also works on headless machines.
+    /// The viewports of `with_layout` are not drawn; each image shows the camera of its window.
+    /// Fails if `width` or `height` is zero, if there is no adapter to render with,
+    /// or if a layer changed by name does not exist.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn render_offscreen(self, width: u32, height: u32) -> Fallible<Vec<image::RgbaImage>> {
+        if width == 0 || height == 0 {
+            failure::bail!("cannot render an image of {}x{} pixels", width, height);
+        }
+
+        futures::executor::block_on(async {
+            let mut images = vec![];
+            for (builder, scene) in self.windows {
+                let pipe = scene.into_builder();
+                let mut window = HeadlessWindow::new(width, height, builder, pipe).await?;
+                images.push(window.render());
+            }
+            Ok(images)
+        })
     }
 
     fn compile(self) -> EngineBuilder<N> {
         EngineBuilder {
-            windows: self.windows,
+            windows: self
+                .windows
+                .into_iter()
+                .map(|(builder, scene)| (builder, scene.into_builder()))
+                .collect(),
+            commands: self.command_receiver,
         }
     }
 }
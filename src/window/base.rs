@@ -1,13 +1,27 @@
-use super::builder::WindowBuilder;
-use super::camera::{Camera, CameraController};
+use super::builder::{ErrorCallback, WindowBuilder};
+use super::camera::Camera;
+use super::capture::{Capture, WindowCommand};
+use super::context::{render_pass, request_device, RenderTarget};
 use super::event::WindowEventState;
-use super::uniform::Uniforms;
-use crate::pipes::{PipelineBuilder, PipelineRenderer, VertexFormat};
-
+use super::highlight::Highlight;
+use super::input::{Action, InputMap, Mapped};
+use super::overlay::Overlay;
+use super::uniform::create_bind_group_layout;
+use super::viewport::{ViewLayout, Viewport};
+use crate::pipes::{DepthTexture, LayerChange, PipelineBuilder, PipelineRenderer, VertexFormat};
+
+use failure::Fallible;
 use nalgebra::{Point3, Vector2};
 use slam_cv::Number;
 use winit::{event::*, window};
 
+/// How far from the cursor the data is picked, in pixels.
+const PICK_TOLERANCE: f32 = 8.0;
+/// How far the cursor may move for a click to pick, in pixels.
+const CLICK_DISTANCE: f64 = 3.0;
+/// The half size of the cross over the picked data, in pixels.
+const HIGHLIGHT_SIZE: f32 = 12.0;
+
 pub struct Window<N>
 where
     N: 'static + Number,
@@ -19,18 +33,29 @@ where
     queue: wgpu::Queue,
     sc_desc: wgpu::SwapChainDescriptor,
     swap_chain: wgpu::SwapChain,
+    depth_texture: DepthTexture,
 
     pipeline_rendener: Box<dyn PipelineRenderer>,
+    highlight: Highlight<N>,
+    overlay: Overlay,
 
     // TODO move camera to ShaderPlugin
-    camera: Camera<N>,
-    camera_controller: CameraController<N>,
+    viewports: Vec<Viewport<N>>,
+    /// The viewport under the cursor, which the camera inputs move.
+    active: usize,
+    layout: ViewLayout,
+    link_cameras: bool,
+    is_dragging: bool,
+
+    input_map: InputMap,
+    cursor: Vector2<f64>,
+    click: Option<Vector2<f64>>,
+    pick_pending: Option<Vector2<f64>>,
 
     pub framerate: Option<u64>,
 
-    uniforms: Uniforms<N>,
-    uniform_buffer: wgpu::Buffer,
-    uniform_bind_group: wgpu::BindGroup,
+    capture: Capture,
+    error_callback: Option<ErrorCallback>,
 }
 
 impl<N> Window<N>
@@ -63,28 +88,8 @@ where
         let instance = wgpu::Instance::new();
         let surface = unsafe { instance.create_surface(&window) };
 
-        let adapter = instance
-            .request_adapter(
-                &wgpu::RequestAdapterOptions {
-                    power_preference: wgpu::PowerPreference::Default,
-                    compatible_surface: Some(&surface),
-                },
-                // Vulkan + Metal + DX12 + Browser WebGPU
-                wgpu::BackendBit::PRIMARY,
-            )
-            .await
-            .unwrap();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    extensions: wgpu::Extensions {
-                        anisotropic_filtering: false,
-                    },
-                    limits: Default::default(),
-                },
-                None,
-            )
+        // Vulkan + Metal + DX12 + Browser WebGPU
+        let (device, queue) = request_device(&instance, Some(&surface), wgpu::BackendBit::PRIMARY)
             .await
             .unwrap();
 
@@ -102,106 +107,264 @@ where
             present_mode: wgpu::PresentMode::Immediate,
         };
         let swap_chain = device.create_swap_chain(&surface, &sc_desc);
+        let depth_texture = DepthTexture::new(&device, sc_desc.width, sc_desc.height);
+
+        let bind_group_layout = create_bind_group_layout(&device);
+
+        let camera: Camera<N> = builder.camera.into();
+        let camera_controller = builder.camera_controller;
+        let layout = builder.layout;
+        let mut viewports: Vec<_> = layout
+            .cameras()
+            .into_iter()
+            .zip(layout.rects(sc_desc.width, sc_desc.height))
+            .map(|(kind, rect)| {
+                Viewport::new(
+                    &device,
+                    sc_desc.format,
+                    &bind_group_layout,
+                    rect,
+                    kind,
+                    camera.clone(),
+                    camera_controller.clone(),
+                )
+            })
+            .collect();
+        for viewport in &mut viewports {
+            viewport.upload(&queue);
+        }
 
-        let camera = builder.camera.into();
-        let mut camera_controller: CameraController<N> = builder.camera_controller.into();
-
-        camera_controller.window_size =
-            Vector2::new(N::from(size.width).unwrap(), N::from(size.height).unwrap());
-
-        let mut uniforms = Uniforms::default();
-        uniforms.update_view_proj(&camera, Self::aspect(&sc_desc));
-
-        let uniform_buffer = device.create_buffer_with_data(
-            bytemuck::cast_slice(&[uniforms]),
-            wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
-        );
+        let mut pipeline_rendener =
+            pipeline_builder.build(&device, sc_desc.format, &bind_group_layout);
+        let layers = LayerChange::apply_all(&builder.layers, pipeline_rendener.as_mut());
 
-        let uniform_bind_group_layout =
-            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                bindings: &[wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStage::VERTEX,
-                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
-                }],
-                label: Some("uniform_bind_group_layout"),
-            });
-
-        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &uniform_bind_group_layout,
-            bindings: &[wgpu::Binding {
-                binding: 0,
-                resource: wgpu::BindingResource::Buffer {
-                    buffer: &uniform_buffer,
-                    // FYI: you can share a single buffer between bindings.
-                    range: 0..std::mem::size_of_val(&uniforms) as wgpu::BufferAddress,
-                },
-            }],
-            label: Some("uniform_bind_group"),
-        });
-
-        let pipeline_rendener =
-            pipeline_builder.build(&device, sc_desc.format, &uniform_bind_group_layout);
+        let highlight = Highlight::new(&device, sc_desc.format, &bind_group_layout);
+        let overlay = Overlay::new(&device, sc_desc.format);
 
         let framerate = builder.framerate;
+        let input_map = builder.input_map;
 
-        Self {
+        let mut engine_window = Self {
             window,
             surface,
             device,
             queue,
             sc_desc,
             swap_chain,
+            depth_texture,
 
             pipeline_rendener,
+            highlight,
+            overlay,
+
+            viewports,
+            active: 0,
+            layout,
+            link_cameras: builder.link_cameras,
+            is_dragging: false,
 
-            camera,
-            camera_controller,
+            input_map,
+            cursor: Vector2::zeros(),
+            click: None,
+            pick_pending: None,
 
             framerate,
 
-            uniforms,
-            uniform_buffer,
-            uniform_bind_group,
-        }
+            capture: Capture::default(),
+            error_callback: builder.error_callback,
+        };
+        engine_window.report(layers);
+        engine_window
     }
 
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         self.sc_desc.width = new_size.width;
         self.sc_desc.height = new_size.height;
         self.swap_chain = self.device.create_swap_chain(&self.surface, &self.sc_desc);
+        self.depth_texture = DepthTexture::new(&self.device, new_size.width, new_size.height);
+
+        let rects = self.layout.rects(new_size.width, new_size.height);
+        for (viewport, rect) in self.viewports.iter_mut().zip(rects) {
+            viewport.resize(rect);
+        }
     }
 
     pub fn input(&mut self, event: &WindowEvent) -> WindowEventState {
-        self.camera_controller.process_events(event)
+        if let WindowEvent::CursorMoved { position, .. } = event {
+            self.cursor = Vector2::new(position.x, position.y);
+
+            // a drag keeps moving the camera it started on
+            if !self.is_dragging {
+                if let Some(active) = self.viewport_at(self.cursor) {
+                    self.active = active;
+                }
+            }
+        }
+
+        // the clicks on the overlay do not reach the camera
+        if let WindowEventState::Consumed = self.overlay.input(event) {
+            self.click = None;
+            return WindowEventState::Consumed;
+        }
+
+        match self.input_map.map(event) {
+            Mapped::Action(Action::Screenshot, is_pressed) => {
+                if is_pressed {
+                    let result = self.capture.screenshot();
+                    self.report(result);
+                }
+                WindowEventState::Consumed
+            }
+            Mapped::Action(Action::ToggleRecording, is_pressed) => {
+                if is_pressed {
+                    let result = self.capture.toggle_recording();
+                    self.report(result);
+                }
+                WindowEventState::Consumed
+            }
+            Mapped::Action(Action::ToggleLayer(index), is_pressed) => {
+                if is_pressed {
+                    if let Some(layer) = self.pipeline_rendener.layers().into_iter().nth(index) {
+                        layer.set_visible(!layer.is_visible());
+                    }
+                }
+                WindowEventState::Consumed
+            }
+            Mapped::Action(Action::ToggleLegend, is_pressed) => {
+                if is_pressed {
+                    self.overlay.legend = !self.overlay.legend;
+                }
+                WindowEventState::Consumed
+            }
+            Mapped::Action(Action::ToggleOverlay, is_pressed) => {
+                if is_pressed {
+                    self.overlay.toggle();
+                }
+                WindowEventState::Consumed
+            }
+            Mapped::Action(Action::Quit, true) => WindowEventState::Exit,
+            Mapped::Action(Action::Quit, false) => WindowEventState::Consumed,
+            Mapped::Action(Action::Pick, is_pressed) => {
+                if is_pressed {
+                    self.pick_pending = Some(self.cursor);
+                }
+                WindowEventState::Consumed
+            }
+            Mapped::Action(Action::Rotate, is_pressed) => {
+                // a click without a drag picks as well
+                if is_pressed {
+                    self.click = Some(self.cursor);
+                } else if let Some(click) = self.click.take() {
+                    if (self.cursor - click).norm() <= CLICK_DISTANCE {
+                        self.pick_pending = Some(self.cursor);
+                    }
+                }
+                self.camera_action(Action::Rotate, is_pressed)
+            }
+            Mapped::Action(action, is_pressed) => self.camera_action(action, is_pressed),
+            Mapped::Called => WindowEventState::Consumed,
+            Mapped::Unbound => match event {
+                // the viewports are resized by the window
+                WindowEvent::Resized(_) => WindowEventState::Unused,
+                WindowEvent::CursorMoved { .. } | WindowEvent::ModifiersChanged(_) => self
+                    .viewports
+                    .iter_mut()
+                    .map(|v| v.camera_controller.process_events(event))
+                    .fold(WindowEventState::Unused, |state, result| match result {
+                        WindowEventState::Unused => state,
+                        result => result,
+                    }),
+                _ => self.viewports[self.active]
+                    .camera_controller
+                    .process_events(event),
+            },
+        }
+    }
+
+    /// Moves the camera of the active viewport, while the releases reach all of them so that no
+    /// input is left pressed.
+    fn camera_action(&mut self, action: Action, is_pressed: bool) -> WindowEventState {
+        if let Action::Rotate | Action::Pan = action {
+            self.is_dragging = is_pressed;
+        }
+
+        if is_pressed {
+            self.viewports[self.active]
+                .camera_controller
+                .process_action(action, is_pressed)
+        } else {
+            self.viewports
+                .iter_mut()
+                .map(|v| v.camera_controller.process_action(action, is_pressed))
+                .fold(WindowEventState::Unused, |state, result| match result {
+                    WindowEventState::Unused => state,
+                    result => result,
+                })
+        }
+    }
+
+    fn viewport_at(&self, cursor: Vector2<f64>) -> Option<usize> {
+        self.viewports.iter().position(|v| v.rect.contains(cursor))
+    }
+
+    pub fn command(&mut self, command: WindowCommand) {
+        match command {
+            // sent to every window, which may not have the layer
+            WindowCommand::Layer(name, change) => {
+                change.apply(self.pipeline_rendener.layers(), &name);
+            }
+            command => {
+                let result = self.capture.command(command);
+                self.report(result);
+            }
+        }
+    }
+
+    /// Tells the application about the failure, if it listens.
+    fn report(&mut self, result: Fallible<()>) {
+        if let (Err(error), Some(callback)) = (result, &mut self.error_callback) {
+            callback(&error);
+        }
+    }
+
+    pub fn sync(&mut self) {
+        self.pipeline_rendener.sync();
     }
 
     pub fn update(&mut self) {
-        self.camera_controller.update_camera(&mut self.camera);
-        self.uniforms
-            .update_view_proj(&self.camera, Self::aspect(&self.sc_desc));
-
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("update encoder"),
-            });
-        let staging_buffer = self.device.create_buffer_with_data(
-            bytemuck::cast_slice(&[self.uniforms]),
-            wgpu::BufferUsage::COPY_SRC,
-        );
+        let renderer = self.pipeline_rendener.as_ref();
+        let moved: Vec<_> = self
+            .viewports
+            .iter_mut()
+            .map(|viewport| viewport.update(renderer))
+            .collect();
+
+        if self.link_cameras {
+            let moved = moved[self.active];
+            for (index, viewport) in self.viewports.iter_mut().enumerate() {
+                if index != self.active {
+                    viewport.follow(moved);
+                }
+            }
+        }
 
-        encoder.copy_buffer_to_buffer(
-            &staging_buffer,
-            0,
-            &self.uniform_buffer,
-            0,
-            std::mem::size_of::<Uniforms<N>>() as wgpu::BufferAddress,
-        );
+        for viewport in &mut self.viewports {
+            viewport.upload(&self.queue);
+        }
 
-        // We need to remember to submit our CommandEncoder's output
-        // otherwise we won't see any change.
-        self.queue.submit(Some(encoder.finish()));
+        if let Some(cursor) = self.pick_pending.take() {
+            self.pick(cursor);
+        }
+        for error in self.capture.errors() {
+            self.report(Err(error));
+        }
+        self.overlay.update(
+            &self.queue,
+            &self.viewports[self.active].camera,
+            self.pipeline_rendener.as_mut(),
+            self.sc_desc.width,
+            self.sc_desc.height,
+        );
     }
 
     pub fn render(&mut self) {
@@ -210,37 +373,142 @@ where
             .get_next_texture()
             .expect("Timeout getting texture");
 
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
+        let encoder = encode_viewports(
+            &self.device,
+            &self.queue,
+            &frame.view,
+            &self.depth_texture,
+            &mut self.viewports,
+            [self.pipeline_rendener.as_mut(), &mut self.highlight],
+            &mut self.overlay,
+        );
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                    attachment: &frame.view,
-                    resolve_target: None,
-                    load_op: wgpu::LoadOp::Clear,
-                    store_op: wgpu::StoreOp::Store,
-                    clear_color: wgpu::Color::BLACK,
-                }],
-                depth_stencil_attachment: None,
-            });
-
-            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-            self.pipeline_rendener
-                .render(&self.device, &mut render_pass);
+        self.queue.submit(Some(encoder.finish()));
+
+        if self.capture.is_pending() {
+            self.capture_frame();
+        }
+    }
+
+    /// Highlights the data under the cursor, and tells the application about it.
+    fn pick(&mut self, cursor: Vector2<f64>) {
+        let viewport = match self.viewport_at(cursor) {
+            Some(index) => &self.viewports[index],
+            None => return,
+        };
+
+        let cast = |v: f64| N::from(v).unwrap();
+        let ray = viewport.camera.pick_ray(
+            viewport.rect.local(cursor).map(cast),
+            viewport.rect.size().map(cast),
+            cast(PICK_TOLERANCE as f64),
+        );
+        let pick = ray.and_then(|ray| Some((ray, self.pipeline_rendener.pick(&ray)?)));
+
+        match pick {
+            Some((ray, pick)) => {
+                let size = ray.tolerance(pick.depth) / PICK_TOLERANCE * HIGHLIGHT_SIZE;
+                self.highlight.set(Some(&pick), size);
+                self.input_map.picked(&pick);
+            }
+            None => self.highlight.set(None, 0.0),
         }
+    }
+
+    /// Renders the frame again into an offscreen target, as a swap chain cannot be read.
+    ///
+    /// The frame is encoded and saved by the capture in the background,
+    /// but reading it back waits for the GPU, so capturing slows the window down.
+    fn capture_frame(&mut self) {
+        let target = self.capture.target(
+            &self.device,
+            self.sc_desc.width,
+            self.sc_desc.height,
+            self.sc_desc.format,
+        );
+
+        let mut encoder = encode_viewports(
+            &self.device,
+            &self.queue,
+            target.view(),
+            target.depth_texture(),
+            &mut self.viewports,
+            [self.pipeline_rendener.as_mut(), &mut self.highlight],
+            &mut self.overlay,
+        );
+        target.copy_to_buffer(&mut encoder);
 
         self.queue.submit(Some(encoder.finish()));
+
+        let image = target.read(&self.device);
+        self.capture.save(image);
     }
 
     pub fn request_redraw(&self) {
         self.window.request_redraw();
     }
+}
 
-    fn aspect(sc_desc: &wgpu::SwapChainDescriptor) -> N {
-        N::from(sc_desc.width).unwrap() / N::from(sc_desc.height).unwrap()
+/// Encodes a render pass for each viewport, drawing the pipelines and its gizmo, and then one
+/// drawing the overlay over the whole target.
+fn encode_viewports<N>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    view: &wgpu::TextureView,
+    depth_texture: &DepthTexture,
+    viewports: &mut [Viewport<N>],
+    pipeline_rendeners: [&mut dyn PipelineRenderer; 2],
+    overlay: &mut Overlay,
+) -> wgpu::CommandEncoder
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Render Encoder"),
+    });
+
+    // the data is shared by the viewports, which only draw it
+    let [pipeline_rendener, highlight] = pipeline_rendeners;
+    pipeline_rendener.prepare(device, queue);
+    highlight.prepare(device, queue);
+
+    for (index, viewport) in viewports.iter_mut().enumerate() {
+        viewport.gizmo.prepare(device, queue);
+        render_pass(
+            &mut encoder,
+            device,
+            queue,
+            RenderTarget {
+                view,
+                depth_texture,
+                clear: index == 0,
+                viewport: Some(viewport.rect),
+            },
+            &viewport.uniforms.bind_group,
+            &mut [
+                &mut *pipeline_rendener,
+                &mut *highlight,
+                &mut viewport.gizmo,
+            ],
+        );
     }
+
+    // the overlay binds its own uniforms
+    overlay.prepare(device, queue);
+    render_pass(
+        &mut encoder,
+        device,
+        queue,
+        RenderTarget {
+            view,
+            depth_texture,
+            clear: false,
+            viewport: None,
+        },
+        &viewports[0].uniforms.bind_group,
+        &mut [overlay],
+    );
+
+    encoder
 }
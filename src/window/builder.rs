@@ -1,11 +1,16 @@
 use super::base::Window;
 use super::camera::{CameraControllerConfig, CameraFrustum};
-use crate::pipes::{PipelineBuilder, VertexFormat};
+use super::input::InputMap;
+use super::viewport::ViewLayout;
+use crate::pipes::{LayerChange, PipelineBuilder, VertexFormat};
 
 use nalgebra::Point3;
 use slam_cv::Number;
 use winit::{event_loop::EventLoop, window};
 
+/// A callback of an application, called with the failures of a window, e.g. to save a frame.
+pub type ErrorCallback = Box<dyn FnMut(&failure::Error) + Send>;
+
 pub struct WindowBuilder<N>
 where
     N: 'static + Number,
@@ -16,6 +21,40 @@ where
 
     pub camera: CameraFrustum<N>,
     pub camera_controller: CameraControllerConfig<N>,
+    /// The keys and the mouse buttons, e.g. without `Escape` to quit.
+    pub input_map: InputMap,
+    /// The changes of the layers by name, applied once the pipelines are built.
+    pub layers: Vec<(String, LayerChange)>,
+
+    /// The viewports of the window, e.g. `ViewLayout::QUAD`.
+    ///
+    /// Screenshots and recordings draw every viewport again,
+    /// but a `HeadlessWindow` draws only the camera of the window.
+    pub layout: ViewLayout,
+    /// Whether the other viewports move along with the camera moved by the user.
+    pub link_cameras: bool,
+
+    /// Called with the failures of the window, which are ignored without it.
+    pub error_callback: Option<ErrorCallback>,
+}
+
+impl Default for WindowBuilder<f32> {
+    fn default() -> Self {
+        Self {
+            title: None,
+            framerate: Some(120),
+
+            camera: CameraFrustum::default(),
+            camera_controller: CameraControllerConfig::default(),
+            input_map: InputMap::default(),
+            layers: vec![],
+
+            layout: ViewLayout::default(),
+            link_cameras: false,
+
+            error_callback: None,
+        }
+    }
 }
 
 impl<N> WindowBuilder<N>
@@ -2,12 +2,17 @@
 //! https://github.com/sebcrozet/kiss3d/blob/master/src/camera/first_person.rs
 
 use super::coord::CoordSystemRh;
+use super::tracking::KeyFrameView;
+use super::view::Projection;
+use crate::pipes::PickRay;
 
-use nalgebra::{Isometry3, Matrix4, Perspective3, Point3, Vector2, Vector3};
+use nalgebra::{
+    Isometry3, Matrix4, Orthographic3, Perspective3, Point3, Unit, Vector2, Vector3, Vector4,
+};
 use num::Float;
 use slam_cv::Number;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Camera<N>
 where
     N: Number,
@@ -15,12 +20,20 @@ where
     pub eye: Point3<N>,
     pub yaw: N,
     pub pitch: N,
+    /// The point the orbit mode turns around.
+    pub pivot: Point3<N>,
 
+    pub projection: Projection,
     pub fovy: N,
+    /// The half of the height seen by the orthographic projection.
+    pub ortho_height: N,
     pub znear: N,
     pub zfar: N,
 
     pub coord_system: CoordSystemRh<N>,
+
+    /// If set, the camera looks through this keyframe instead.
+    pub keyframe: Option<KeyFrameView>,
 }
 
 impl<N> Camera<N>
@@ -40,14 +53,76 @@ where
         self.eye = eye;
         self.yaw = yaw;
         self.pitch = pitch;
+        self.pivot = at;
     }
 
     pub fn compute_view_proj(&self, aspect: N) -> Matrix4<N> {
-        let proj = Perspective3::new(aspect, self.fovy, self.znear, self.zfar);
+        if let Some(keyframe) = &self.keyframe {
+            let cast = |v: N| num::cast::<N, f32>(v).unwrap();
+            let view_proj = keyframe.view_proj(
+                cast(aspect),
+                cast(self.fovy),
+                cast(self.znear),
+                cast(self.zfar),
+            );
+            return view_proj.map(|v| N::from(v).unwrap());
+        }
+
+        let proj = match self.projection {
+            Projection::Perspective => {
+                Perspective3::new(aspect, self.fovy, self.znear, self.zfar).to_homogeneous()
+            }
+            Projection::Orthographic => {
+                let height = self.ortho_height;
+                let width = height * aspect;
+
+                // nothing is behind an orthographic camera
+                Orthographic3::new(-width, width, -height, height, -self.zfar, self.zfar)
+                    .to_homogeneous()
+            }
+        };
 
         let view = self.view_transform().to_homogeneous();
 
-        proj.as_matrix() * view
+        proj * view
+    }
+
+    /// The view transform of the camera, or of the keyframe it looks through.
+    pub fn view_matrix(&self) -> Matrix4<N> {
+        match &self.keyframe {
+            Some(keyframe) => keyframe.view().map(|v| N::from(v).unwrap()),
+            None => self.view_transform().to_homogeneous(),
+        }
+    }
+
+    /// Switches the projection, keeping the scale of the objects at the pivot.
+    pub fn set_projection(&mut self, projection: Projection) {
+        if projection == Projection::Orthographic && self.projection != projection {
+            self.ortho_height = self.pivot_height();
+        }
+        self.projection = projection;
+    }
+
+    /// The half of the height seen at the distance of the pivot.
+    pub fn pivot_height(&self) -> N {
+        let two = N::one() + N::one();
+        (self.pivot - self.eye).norm() * Float::tan(self.fovy / two)
+    }
+
+    /// Shrinks the orthographic view by `factor`.
+    pub fn zoom_orthographic(&mut self, factor: N) {
+        self.ortho_height *= factor;
+    }
+
+    /// Turns the camera around the pivot to the given orientation, keeping its distance.
+    pub fn orbit_to(&mut self, yaw: N, pitch: N) {
+        let dist = (self.pivot - self.eye).norm();
+
+        self.yaw = yaw;
+        self.pitch = pitch;
+        self.update_restrictions();
+
+        self.eye = self.pivot - self.direction() * dist;
     }
 
     pub fn rotate(&mut self, dpos: Vector2<N>) {
@@ -72,6 +147,82 @@ where
         self.eye += front * yoff;
     }
 
+    /// Turns the camera around the pivot, keeping its distance.
+    pub fn orbit(&mut self, dpos: Vector2<N>) {
+        let dist = (self.pivot - self.eye).norm();
+
+        self.rotate(dpos);
+        self.eye = self.pivot - self.direction() * dist;
+    }
+
+    /// Moves both the camera and the pivot, faster when the pivot is far away.
+    pub fn pan(&mut self, dpos: Vector2<N>) {
+        let dist = (self.pivot - self.eye).norm();
+        let eye = self.eye;
+
+        self.move_to(dpos * dist);
+        self.pivot += self.eye - eye;
+    }
+
+    /// Moves the camera towards the pivot by the ratio `1 - factor`, without passing it.
+    pub fn zoom(&mut self, factor: N) {
+        let min_dist = self.znear;
+        let dist = (self.pivot - self.eye).norm() * factor;
+
+        self.eye = self.pivot - self.direction() * Float::max(dist, min_dist);
+    }
+
+    /// Moves the camera back along its direction until the sphere fills the view,
+    /// and fits the clipping planes around it.
+    pub fn frame(&mut self, center: Point3<N>, radius: N) {
+        let two = N::one() + N::one();
+        let radius = Float::max(radius, N::from(1e-3).unwrap());
+        let dist = radius / Float::sin(self.fovy / two);
+
+        self.eye = center - self.direction() * dist;
+        self.pivot = center;
+        self.ortho_height = radius;
+
+        // leave some room to move around
+        self.znear = radius * N::from(1e-3).unwrap();
+        self.zfar = (dist + radius) * N::from(10.0).unwrap();
+    }
+
+    /// The ray through the pixel `cursor` from the top left, widened by `tolerance` pixels.
+    pub fn pick_ray(&self, cursor: Vector2<N>, size: Vector2<N>, tolerance: N) -> Option<PickRay> {
+        let (one, two) = (N::one(), N::one() + N::one());
+        let inverse = self.compute_view_proj(size.x / size.y).try_inverse()?;
+
+        // from the near plane into the view, in the normalized device coordinates
+        let unproject = |x: N, y: N, z: N| {
+            let ndc = Vector4::new(two * x / size.x - one, one - two * y / size.y, z, one);
+            let point = inverse * ndc;
+            Point3::from(point.xyz() / point.w).map(|v| num::cast::<N, f32>(v).unwrap())
+        };
+        let (near, far) = (-one, N::from(0.5).unwrap());
+
+        let origin = unproject(cursor.x, cursor.y, near);
+        let end = unproject(cursor.x, cursor.y, far);
+        let origin_side = unproject(cursor.x + tolerance, cursor.y, near);
+        let end_side = unproject(cursor.x + tolerance, cursor.y, far);
+
+        let length = (end - origin).norm();
+        let radius = (origin_side - origin).norm();
+        let spread = ((end_side - end).norm() - radius) / length;
+
+        Some(PickRay {
+            origin,
+            direction: Unit::new_normalize(end - origin),
+            radius,
+            spread,
+        })
+    }
+
+    /// The unit vector the camera is looking along.
+    fn direction(&self) -> Vector3<N> {
+        (self.at() - self.eye).normalize()
+    }
+
     /// The point the camera is looking at.
     fn at(&self) -> Point3<N> {
         let view_eye = self.coord_system.rotation_to_y_up * self.eye;
@@ -104,3 +255,106 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::frustrum::CameraFrustum;
+    use super::*;
+
+    fn camera(up_axis: Unit<Vector3<f32>>) -> Camera<f32> {
+        CameraFrustum {
+            eye: Point3::new(3.0, 2.0, 5.0),
+            at: Point3::new(1.0, -1.0, 0.5),
+            up_axis,
+
+            projection: Projection::Perspective,
+            fovy: std::f32::consts::FRAC_PI_4,
+            znear: 0.1,
+            zfar: 100.0,
+        }
+        .into()
+    }
+
+    fn cameras() -> Vec<Camera<f32>> {
+        vec![camera(Vector3::y_axis()), camera(Vector3::z_axis())]
+    }
+
+    fn distance(camera: &Camera<f32>) -> f32 {
+        (camera.pivot - camera.eye).norm()
+    }
+
+    #[test]
+    fn orbit_keeps_the_distance() {
+        for mut camera in cameras() {
+            let dist = distance(&camera);
+
+            camera.orbit(Vector2::new(0.7, -0.3));
+            assert!((distance(&camera) - dist).abs() < 1e-4);
+            assert!((camera.direction() - (camera.pivot - camera.eye) / dist).norm() < 1e-4);
+
+            // beyond the pole, which is clamped
+            camera.orbit(Vector2::new(-2.0, 5.0));
+            assert!((distance(&camera) - dist).abs() < 1e-4);
+
+            camera.orbit_to(1.0, 0.5);
+            assert!((distance(&camera) - dist).abs() < 1e-4);
+            assert_eq!((camera.yaw, camera.pitch), (1.0, 0.5));
+        }
+    }
+
+    #[test]
+    fn zoom_stops_before_the_pivot() {
+        for mut camera in cameras() {
+            let dist = distance(&camera);
+            let direction = camera.direction();
+
+            camera.zoom(0.5);
+            assert!((distance(&camera) - dist * 0.5).abs() < 1e-4);
+
+            for _ in 0..100 {
+                camera.zoom(0.1);
+            }
+            assert!(distance(&camera) >= camera.znear * 0.999);
+            // still in front of the pivot
+            assert!((camera.pivot - camera.eye).dot(&direction) > 0.0);
+        }
+    }
+
+    #[test]
+    fn pan_moves_the_pivot_along() {
+        for mut camera in cameras() {
+            let (eye, pivot) = (camera.eye, camera.pivot);
+            let direction = camera.direction();
+
+            camera.pan(Vector2::new(0.2, -0.1));
+            let moved = camera.eye - eye;
+            assert!(moved.norm() > 0.1);
+            assert!((camera.pivot - pivot - moved).norm() < 1e-5);
+            // across the view, keeping the direction
+            assert!(moved.dot(&direction).abs() < 1e-4);
+            assert!((camera.direction() - direction).norm() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn pick_ray_through_the_center() {
+        for mut camera in cameras() {
+            let size = Vector2::new(800.0, 600.0);
+
+            let ray = camera.pick_ray(size / 2.0, size, 5.0).unwrap();
+            let depth = ray.hit(&camera.pivot).unwrap();
+            assert!((depth + camera.znear - distance(&camera)).abs() < 1e-3);
+            assert!((ray.direction.into_inner() - camera.direction()).norm() < 1e-4);
+            assert!(ray.spread > 0.0);
+
+            // a corner is far from the center
+            let ray = camera.pick_ray(Vector2::zeros(), size, 5.0).unwrap();
+            assert!(ray.hit(&camera.pivot).is_none());
+
+            camera.set_projection(Projection::Orthographic);
+            let ray = camera.pick_ray(size / 2.0, size, 5.0).unwrap();
+            assert!(ray.hit(&camera.pivot).is_some());
+            assert!(ray.spread.abs() < 1e-4);
+        }
+    }
+}
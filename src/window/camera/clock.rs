@@ -0,0 +1,32 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// The time step where there is no clock, as a 60 Hz display.
+const FALLBACK_STEP: f32 = 1.0 / 60.0;
+/// Longer steps, e.g. after the window was hidden, do not make the camera jump.
+const MAX_STEP: f32 = 0.1;
+
+/// Measures the time between two updates of the camera.
+#[derive(Default)]
+pub struct FrameClock {
+    #[cfg(not(target_arch = "wasm32"))]
+    last: Option<Instant>,
+}
+
+impl FrameClock {
+    /// The seconds since the last call.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn tick(&mut self) -> f32 {
+        let now = Instant::now();
+
+        match self.last.replace(now) {
+            Some(last) => (now - last).as_secs_f32().min(MAX_STEP),
+            None => FALLBACK_STEP,
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn tick(&mut self) -> f32 {
+        FALLBACK_STEP
+    }
+}
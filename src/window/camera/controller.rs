@@ -2,29 +2,87 @@
 //! https://github.com/sebcrozet/kiss3d/blob/master/src/camera/first_person.rs
 
 use super::super::event::WindowEventState;
+use super::super::input::Action;
 use super::base::Camera;
+use super::clock::FrameClock;
+use super::tracking::{CameraTracking, KeyFrameView};
+use super::view::{Projection, ViewPreset};
+use crate::pipes::Bounds;
 
-use nalgebra::Vector2;
+use nalgebra::{Point3, Vector2, Vector3};
+use num::Float;
 use slam_cv::Number;
 use winit::event::*;
 
+/// The size of the data the speeds are given for, a map of a few meters.
+const REFERENCE_RADIUS: f32 = 5.0;
+/// The seconds to turn the camera to a preset.
+const ANIMATION_SECONDS: f32 = 0.3;
+/// The pixels of a trackpad scroll that make one step of a mouse wheel.
+const PIXELS_PER_LINE: f64 = 20.0;
+
+/// How the mouse and the keys move the camera.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Rotates around the eye and flies, like a game.
+    FirstPerson,
+    /// Turns around a pivot, zooms to it and pans it, like a CAD tool.
+    Orbit,
+}
+
+impl Default for CameraMode {
+    fn default() -> Self {
+        Self::FirstPerson
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct CameraControllerConfig<N>
 where
     N: Number,
 {
+    pub mode: CameraMode,
+    /// The initial tracking, cycled with `T`.
+    pub tracking: CameraTracking,
+    /// The position of the following camera in the keyframe, e.g. above and behind it.
+    pub follow_offset: Vector3<N>,
+    /// The seconds the following camera takes to catch up most of the way; `0` is rigid.
+    pub follow_time: N,
+    /// Frames the data once it is first drawn; `F` frames it again anytime.
+    pub fit_on_startup: bool,
+
+    /// The radians of a drag over the whole window.
     pub mouse_left_speed: N,
+    /// The distance of a drag over the whole window, relative to the size of the data.
     pub mouse_right_speed: N,
+    /// The distance of a scroll step, relative to the size of the data.
     pub scroll_speed: N,
+    /// The distance per second, relative to the size of the data.
     pub keyboard_speed: N,
+    /// The seconds the camera keeps gliding after a drag or a scroll; `0` stops it at once.
+    pub damping: N,
+    /// Multiplies the moves while `Shift` is held.
+    pub fast_multiplier: N,
+    /// Multiplies the moves while `Ctrl` is held.
+    pub slow_multiplier: N,
 }
 
 impl Default for CameraControllerConfig<f32> {
     fn default() -> Self {
         Self {
+            mode: CameraMode::default(),
+            tracking: CameraTracking::default(),
+            follow_offset: Vector3::new(0.0, -0.5, -2.0),
+            follow_time: 0.15,
+            fit_on_startup: true,
+
             mouse_left_speed: 1.0,
             mouse_right_speed: 5.0,
             scroll_speed: 1.0,
-            keyboard_speed: 0.1,
+            keyboard_speed: 6.0,
+            damping: 0.0,
+            fast_multiplier: 5.0,
+            slow_multiplier: 0.2,
         }
     }
 }
@@ -35,14 +93,24 @@ where
 {
     fn into(self) -> CameraController<N> {
         CameraController {
+            tracking: self.tracking,
+            fit_pending: self.fit_on_startup,
             config: self,
 
             window_size: Vector2::zeros(),
+            clock: FrameClock::default(),
+            dt: N::zero(),
 
             cursor_d: Vector2::zeros(),
             cursor_pos: None,
             mouse_wheel_d: N::zero(),
 
+            rotate_velocity: Vector2::zeros(),
+            pan_velocity: Vector2::zeros(),
+            scroll_velocity: N::zero(),
+
+            modifiers: ModifiersState::empty(),
+
             is_left_mouse_pressed: false,
             is_right_mouse_pressed: false,
 
@@ -50,8 +118,74 @@ where
             is_right_key_pressed: false,
             is_up_key_pressed: false,
             is_down_key_pressed: false,
+
+            scene_scale: N::one(),
+
+            toggle_projection: false,
+            preset: None,
+            animation: None,
+        }
+    }
+}
+
+/// A turn of the camera around the pivot, to a preset.
+struct ViewAnimation<N>
+where
+    N: Number,
+{
+    from: (N, N),
+    to: (N, N),
+    elapsed: N,
+}
+
+impl<N> ViewAnimation<N>
+where
+    N: Number,
+{
+    fn new(camera: &Camera<N>, preset: ViewPreset) -> Self {
+        let pi = N::from(std::f32::consts::PI).unwrap();
+        let two_pi = pi + pi;
+
+        let (mut yaw, pitch) = preset.orientation();
+
+        // turn the shortest way
+        while yaw - camera.yaw > pi {
+            yaw -= two_pi;
+        }
+        while yaw - camera.yaw < -pi {
+            yaw += two_pi;
+        }
+
+        Self {
+            from: (camera.yaw, camera.pitch),
+            to: (yaw, pitch),
+            elapsed: N::zero(),
         }
     }
+
+    /// Moves the camera `dt` seconds further, returning `false` at the end.
+    fn step(&mut self, camera: &mut Camera<N>, dt: N) -> bool {
+        self.elapsed += dt;
+
+        let t = Float::min(self.elapsed / N::from(ANIMATION_SECONDS).unwrap(), N::one());
+        // smoothstep
+        let t = t * t * (N::from(3.0).unwrap() - (t + t));
+
+        let lerp = |a: N, b: N| a + (b - a) * t;
+        camera.orbit_to(lerp(self.from.0, self.to.0), lerp(self.from.1, self.to.1));
+
+        t < N::one()
+    }
+}
+
+/// What the mouse does to the camera in a frame.
+struct Motion<N>
+where
+    N: Number,
+{
+    rotate: Vector2<N>,
+    pan: Vector2<N>,
+    scroll: N,
 }
 
 pub struct CameraController<N>
@@ -59,12 +193,24 @@ where
     N: Number,
 {
     config: CameraControllerConfig<N>,
+    tracking: CameraTracking,
+    fit_pending: bool,
 
     cursor_d: Vector2<N>,
     cursor_pos: Option<Vector2<N>>,
     mouse_wheel_d: N,
 
+    /// Per second, kept after a drag or a scroll while damped.
+    rotate_velocity: Vector2<N>,
+    pan_velocity: Vector2<N>,
+    scroll_velocity: N,
+
+    modifiers: ModifiersState,
+
     pub(crate) window_size: Vector2<N>,
+    clock: FrameClock,
+    /// The seconds since the last update.
+    dt: N,
 
     is_left_mouse_pressed: bool,
     is_right_mouse_pressed: bool,
@@ -73,6 +219,13 @@ where
     is_right_key_pressed: bool,
     is_up_key_pressed: bool,
     is_down_key_pressed: bool,
+
+    /// Scales the moves of the first person camera, after the size of the data.
+    scene_scale: N,
+
+    toggle_projection: bool,
+    preset: Option<ViewPreset>,
+    animation: Option<ViewAnimation<N>>,
 }
 
 impl<N> CameraController<N>
@@ -86,53 +239,10 @@ where
                 self.window_size.y = N::from(size.height).unwrap();
                 WindowEventState::Unused
             }
-            WindowEvent::KeyboardInput {
-                input:
-                    KeyboardInput {
-                        state,
-                        virtual_keycode: Some(keycode),
-                        ..
-                    },
-                ..
-            } => {
-                let is_pressed = *state == ElementState::Pressed;
-                match keycode {
-                    VirtualKeyCode::A | VirtualKeyCode::Left => {
-                        self.is_left_key_pressed = is_pressed;
-                        WindowEventState::Consumed
-                    }
-                    VirtualKeyCode::D | VirtualKeyCode::Right => {
-                        self.is_right_key_pressed = is_pressed;
-                        WindowEventState::Consumed
-                    }
-                    VirtualKeyCode::W | VirtualKeyCode::Up => {
-                        self.is_up_key_pressed = is_pressed;
-                        WindowEventState::Consumed
-                    }
-                    VirtualKeyCode::S | VirtualKeyCode::Down => {
-                        self.is_down_key_pressed = is_pressed;
-                        WindowEventState::Consumed
-                    }
-                    _ => WindowEventState::Unused,
-                }
-            }
-            WindowEvent::MouseInput { state, button, .. } => {
-                self.cursor_pos = None;
-
-                let is_pressed = *state == ElementState::Pressed;
-                match button {
-                    MouseButton::Left => {
-                        self.is_left_mouse_pressed = is_pressed;
-                        WindowEventState::Consumed
-                    }
-                    MouseButton::Right => {
-                        self.is_right_mouse_pressed = is_pressed;
-                        WindowEventState::Consumed
-                    }
-                    _ => WindowEventState::Unused,
-                }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = *modifiers;
+                WindowEventState::Unused
             }
-            // TODO: calibrate
             WindowEvent::CursorMoved { position, .. } => {
                 if self.is_left_mouse_pressed || self.is_right_mouse_pressed {
                     let position =
@@ -152,8 +262,7 @@ where
             WindowEvent::MouseWheel { delta, .. } => {
                 self.mouse_wheel_d += match delta {
                     MouseScrollDelta::LineDelta(_, ny) => N::from(*ny).unwrap(),
-                    // TODO: calibrate
-                    MouseScrollDelta::PixelDelta(dp) => N::from(dp.x.max(dp.y)).unwrap(),
+                    MouseScrollDelta::PixelDelta(dp) => N::from(dp.y / PIXELS_PER_LINE).unwrap(),
                 };
                 WindowEventState::Consumed
             }
@@ -161,24 +270,229 @@ where
         }
     }
 
+    /// Applies a camera action of the `InputMap`, as its input is pressed or released.
+    pub fn process_action(&mut self, action: Action, is_pressed: bool) -> WindowEventState {
+        match action {
+            Action::Rotate => {
+                self.cursor_pos = None;
+                self.is_left_mouse_pressed = is_pressed;
+            }
+            Action::Pan => {
+                self.cursor_pos = None;
+                self.is_right_mouse_pressed = is_pressed;
+            }
+            Action::MoveLeft => self.is_left_key_pressed = is_pressed,
+            Action::MoveRight => self.is_right_key_pressed = is_pressed,
+            Action::MoveForward => self.is_up_key_pressed = is_pressed,
+            Action::MoveBackward => self.is_down_key_pressed = is_pressed,
+            Action::CycleTracking => {
+                if is_pressed {
+                    self.tracking = self.tracking.next();
+                }
+            }
+            Action::FrameAll => self.fit_pending |= is_pressed,
+            Action::ToggleProjection => self.toggle_projection |= is_pressed,
+            Action::Preset(preset) => {
+                if is_pressed {
+                    self.preset = Some(preset);
+                }
+            }
+            _ => return WindowEventState::Unused,
+        }
+        WindowEventState::Consumed
+    }
+
     pub fn update_camera(&mut self, camera: &mut Camera<N>) {
+        self.dt = N::from(self.clock.tick()).unwrap();
+
         self.cursor_d.x /= self.window_size.x;
         self.cursor_d.y /= self.window_size.y;
 
-        // mouse movement
+        if self.toggle_projection {
+            self.toggle_projection = false;
+            camera.set_projection(match camera.projection {
+                Projection::Perspective => Projection::Orthographic,
+                Projection::Orthographic => Projection::Perspective,
+            });
+        }
+
+        if let Some(preset) = self.preset.take() {
+            self.animation = Some(ViewAnimation::new(camera, preset));
+        }
+        if let Some(animation) = &mut self.animation {
+            if !animation.step(camera, self.dt) {
+                self.animation = None;
+            }
+        }
+
+        // the tracked camera ignores the user
+        if self.tracking == CameraTracking::Free {
+            let motion = self.damp();
+            match self.config.mode {
+                CameraMode::FirstPerson => self.update_first_person(camera, motion),
+                CameraMode::Orbit => self.update_orbit(camera, motion),
+            }
+        }
+
+        self.cursor_d = Vector2::zeros();
+        self.mouse_wheel_d = N::zero();
+    }
+
+    pub fn mode(&self) -> CameraMode {
+        self.config.mode
+    }
+
+    /// Whether the data should be framed, once it has some bounds.
+    pub fn is_fit_pending(&self) -> bool {
+        self.fit_pending
+    }
+
+    /// Frames the data if requested, and adapts the speeds to its size.
+    pub fn fit_bounds(&mut self, camera: &mut Camera<N>, bounds: Option<Bounds>) {
+        if let Some(bounds) = bounds {
+            let center = bounds.center().map(|v| N::from(v).unwrap());
+            let radius = N::from(bounds.radius()).unwrap();
+
+            self.fit(camera, center, radius);
+            self.fit_pending = false;
+        }
+    }
+
+    /// Frames the data, and adapts the speeds to its size.
+    pub fn fit(&mut self, camera: &mut Camera<N>, center: Point3<N>, radius: N) {
+        camera.frame(center, radius);
+        self.scene_scale = radius / N::from(REFERENCE_RADIUS).unwrap();
+        self.animation = None;
+    }
+
+    /// Moves the camera with the latest keyframe, if it is tracked.
+    pub fn track(&self, camera: &mut Camera<N>, keyframe: Option<KeyFrameView>) {
+        camera.keyframe = None;
+
+        let keyframe = match keyframe {
+            Some(keyframe) => keyframe,
+            None => return,
+        };
+
+        match self.tracking {
+            CameraTracking::Free => {}
+            CameraTracking::Follow => {
+                let cast = |v: N| num::cast::<N, f32>(v).unwrap();
+                let uncast = |p: Point3<f32>| p.map(|v| N::from(v).unwrap());
+
+                let offset = Point3::from(self.config.follow_offset.map(cast));
+                let eye = uncast(keyframe.transform(&offset));
+                let at = uncast(keyframe.transform(&Point3::new(0.0, 0.0, 1.0)));
+
+                let time = self.config.follow_time;
+                let weight = if time > N::zero() {
+                    N::one() - Float::exp(-self.dt / time)
+                } else {
+                    N::one()
+                };
+                let eye = camera.eye + (eye - camera.eye) * weight;
+                let at = camera.pivot + (at - camera.pivot) * weight;
+                camera.look_at(Some(eye), at);
+            }
+            CameraTracking::ThroughKeyFrame => camera.keyframe = Some(keyframe),
+        }
+    }
+
+    /// The rotation, the pan and the scroll of this frame, gliding on after them if damped.
+    fn damp(&mut self) -> Motion<N> {
+        let dt = self.dt;
+        let damping = self.config.damping;
+
+        let rotate = if self.is_left_mouse_pressed {
+            self.cursor_d
+        } else {
+            Vector2::zeros()
+        };
+        let pan = if self.is_right_mouse_pressed {
+            self.cursor_d
+        } else {
+            Vector2::zeros()
+        };
+
+        if damping <= N::zero() || dt <= N::zero() {
+            return Motion {
+                rotate,
+                pan,
+                scroll: self.mouse_wheel_d,
+            };
+        }
+
+        let decay = Float::exp(-dt / damping);
         if self.is_left_mouse_pressed {
-            camera.rotate(self.cursor_d * self.config.mouse_left_speed);
+            self.rotate_velocity = rotate / dt;
+        } else {
+            self.rotate_velocity *= decay;
         }
         if self.is_right_mouse_pressed {
-            camera.move_to(self.cursor_d * self.config.mouse_right_speed);
+            self.pan_velocity = pan / dt;
+        } else {
+            self.pan_velocity *= decay;
         }
-        self.cursor_d = Vector2::zeros();
+        // spread each step over the damping time, so that it goes as far as undamped
+        self.scroll_velocity = self.scroll_velocity * decay + self.mouse_wheel_d / damping;
 
-        // scroll movement
-        camera.scale(self.mouse_wheel_d * self.config.scroll_speed);
-        self.mouse_wheel_d = N::zero();
+        Motion {
+            rotate: self.rotate_velocity * dt,
+            pan: self.pan_velocity * dt,
+            scroll: self.scroll_velocity * dt,
+        }
+    }
+
+    /// Multiplies the moves by the held modifiers.
+    fn speed_modifier(&self) -> N {
+        if self.modifiers.shift() {
+            self.config.fast_multiplier
+        } else if self.modifiers.ctrl() {
+            self.config.slow_multiplier
+        } else {
+            N::one()
+        }
+    }
+
+    fn update_first_person(&self, camera: &mut Camera<N>, motion: Motion<N>) {
+        let scale = self.scene_scale * self.speed_modifier();
+
+        // mouse movement
+        camera.rotate(motion.rotate * self.config.mouse_left_speed);
+        camera.move_to(motion.pan * self.config.mouse_right_speed * scale);
 
         // keyboard input
+        let dkey = self.key_direction() * self.config.keyboard_speed * self.dt;
+        camera.move_to(Vector2::new(dkey.x * scale, N::zero()));
+
+        // scroll movement
+        let forward = (motion.scroll * self.config.scroll_speed + dkey.y) * self.speed_modifier();
+        match camera.projection {
+            Projection::Perspective => camera.scale(forward * self.scene_scale),
+            Projection::Orthographic => camera.zoom_orthographic(zoom_factor(forward)),
+        }
+    }
+
+    fn update_orbit(&self, camera: &mut Camera<N>, motion: Motion<N>) {
+        let modifier = self.speed_modifier();
+
+        // mouse movement; a drag over the whole window pans by the distance to the pivot
+        camera.orbit(motion.rotate * self.config.mouse_left_speed);
+        camera.pan(motion.pan * modifier);
+
+        // keyboard input, relative to the distance to the pivot as well
+        let dkey = self.key_direction() * self.config.keyboard_speed * self.dt * modifier;
+        camera.pan(Vector2::new(dkey.x, N::zero()));
+
+        // scroll movement
+        let factor = zoom_factor(motion.scroll * self.config.scroll_speed * modifier + dkey.y);
+        match camera.projection {
+            Projection::Perspective => camera.zoom(factor),
+            Projection::Orthographic => camera.zoom_orthographic(factor),
+        }
+    }
+
+    fn key_direction(&self) -> Vector2<N> {
         let mut dkey = Vector2::<N>::zeros();
         if self.is_left_key_pressed {
             dkey.x += N::one();
@@ -192,9 +506,14 @@ where
         if self.is_down_key_pressed {
             dkey.y -= N::one();
         }
-        dkey *= self.config.keyboard_speed;
-
-        camera.move_to(Vector2::new(dkey.x, N::zero()));
-        camera.scale(dkey.y);
+        dkey
     }
 }
+
+/// Each step of the scroll gets 10% closer.
+fn zoom_factor<N>(steps: N) -> N
+where
+    N: Number,
+{
+    Float::powf(N::from(0.9).unwrap(), steps)
+}
@@ -26,3 +26,27 @@ where
         }
     }
 }
+
+/// A named orientation of the world axes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Convention {
+    /// `y` up, like the default of the viewer.
+    OpenGl,
+    /// `y` down and `z` forward, like the camera frames of OpenCV.
+    OpenCv,
+    /// `z` up and `x` forward, as in ROS REP-103.
+    Ros,
+}
+
+impl Convention {
+    pub fn up_axis<N>(self) -> Unit<Vector3<N>>
+    where
+        N: 'static + Number,
+    {
+        match self {
+            Self::OpenGl => Vector3::y_axis(),
+            Self::OpenCv => -Vector3::y_axis(),
+            Self::Ros => Vector3::z_axis(),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use super::base::Camera;
-use super::coord::CoordSystemRh;
+use super::coord::{Convention, CoordSystemRh};
+use super::view::Projection;
 
-use nalgebra::{Point3, Vector3};
+use nalgebra::{Point3, Unit, Vector3};
 use slam_cv::Number;
 
 pub struct CameraFrustum<N>
@@ -10,12 +11,29 @@ where
 {
     pub eye: Point3<N>,
     pub at: Point3<N>,
+    pub up_axis: Unit<Vector3<N>>,
 
+    pub projection: Projection,
     pub fovy: N,
     pub znear: N,
     pub zfar: N,
 }
 
+impl Default for CameraFrustum<f32> {
+    fn default() -> Self {
+        Self {
+            eye: Point3::new(0., 2., 5.),
+            at: Point3::new(0., 0., 0.),
+            up_axis: Vector3::y_axis(),
+
+            projection: Projection::Perspective,
+            fovy: std::f32::consts::FRAC_PI_4,
+            znear: 0.1,
+            zfar: 100.0,
+        }
+    }
+}
+
 impl<N> Into<Camera<N>> for CameraFrustum<N>
 where
     N: Number,
@@ -25,16 +43,40 @@ where
             eye: Vector3::zeros().into(),
             yaw: N::zero(),
             pitch: N::zero(),
+            pivot: self.at,
 
+            projection: Projection::Perspective,
             fovy: self.fovy,
+            ortho_height: N::one(),
             znear: self.znear,
             zfar: self.zfar,
 
-            coord_system: CoordSystemRh::from_up_axis(Vector3::y_axis()),
+            coord_system: CoordSystemRh::from_up_axis(self.up_axis),
+
+            keyframe: None,
         };
 
         camera.look_at(Some(self.eye), self.at);
+        camera.set_projection(self.projection);
 
         camera
     }
 }
+
+impl<N> CameraFrustum<N>
+where
+    N: 'static + Number,
+{
+    /// Changes the up axis, turning the eye around the target the same way.
+    pub fn set_up_axis(&mut self, up_axis: Unit<Vector3<N>>) {
+        let from = CoordSystemRh::from_up_axis(self.up_axis).rotation_to_y_up;
+        let to = CoordSystemRh::from_up_axis(up_axis).rotation_to_y_up;
+
+        self.eye = self.at + (to.inverse() * from) * (self.eye - self.at);
+        self.up_axis = up_axis;
+    }
+
+    pub fn set_convention(&mut self, convention: Convention) {
+        self.set_up_axis(convention.up_axis());
+    }
+}
@@ -0,0 +1,84 @@
+use nalgebra::{Matrix4, Point3};
+use slam_cv::Number;
+
+/// The pinhole model of a real camera, in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CameraIntrinsics {
+    pub fx: f32,
+    pub fy: f32,
+    pub cx: f32,
+    pub cy: f32,
+
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A VGA camera, as in the TUM RGB-D datasets, for the sources without intrinsics.
+impl Default for CameraIntrinsics {
+    fn default() -> Self {
+        Self {
+            fx: 525.0,
+            fy: 525.0,
+            cx: 319.5,
+            cy: 239.5,
+
+            width: 640,
+            height: 480,
+        }
+    }
+}
+
+impl CameraIntrinsics {
+    /// The corners of the image at the depth, from the top left one clockwise,
+    /// as seen by the camera looking along `+z` with `y` down.
+    pub fn image_plane<N>(&self, depth: N) -> [Point3<N>; 4]
+    where
+        N: Number,
+    {
+        let depth = num::cast::<N, f32>(depth).unwrap();
+        let corner = |u: f32, v: f32| {
+            let x = (u - self.cx) / self.fx * depth;
+            let y = (v - self.cy) / self.fy * depth;
+            Point3::new(x, y, depth).map(|c| N::from(c).unwrap())
+        };
+
+        let w = self.width as f32;
+        let h = self.height as f32;
+        [
+            corner(0.0, 0.0),
+            corner(w, 0.0),
+            corner(w, h),
+            corner(0.0, h),
+        ]
+    }
+
+    /// The projection of the camera looking along `-z` with `y` up, like `Perspective3`.
+    ///
+    /// The image is stretched over the whole target, whatever its aspect ratio is.
+    pub fn projection(&self, znear: f32, zfar: f32) -> Matrix4<f32> {
+        let w = self.width as f32;
+        let h = self.height as f32;
+
+        Matrix4::new(
+            2.0 * self.fx / w,
+            0.0,
+            1.0 - 2.0 * self.cx / w,
+            0.0,
+            //
+            0.0,
+            2.0 * self.fy / h,
+            2.0 * self.cy / h - 1.0,
+            0.0,
+            //
+            0.0,
+            0.0,
+            (zfar + znear) / (znear - zfar),
+            2.0 * zfar * znear / (znear - zfar),
+            //
+            0.0,
+            0.0,
+            -1.0,
+            0.0,
+        )
+    }
+}
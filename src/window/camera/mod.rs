@@ -1,8 +1,17 @@
 mod base;
+mod clock;
 mod controller;
 mod coord;
 mod frustrum;
+mod intrinsics;
+mod tracking;
+mod view;
 
 pub use self::base::Camera;
-pub use self::controller::{CameraController, CameraControllerConfig};
+pub use self::clock::FrameClock;
+pub use self::controller::{CameraController, CameraControllerConfig, CameraMode};
+pub use self::coord::Convention;
 pub use self::frustrum::CameraFrustum;
+pub use self::intrinsics::CameraIntrinsics;
+pub use self::tracking::{CameraTracking, KeyFrameView};
+pub use self::view::{Projection, ViewPreset};
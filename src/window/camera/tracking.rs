@@ -0,0 +1,80 @@
+use super::intrinsics::CameraIntrinsics;
+
+use nalgebra::{Isometry3, Matrix4, Perspective3, Point3, Quaternion, UnitQuaternion, Vector4};
+use slam_cv::Number;
+
+/// Whether the camera moves by itself with the latest keyframe.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CameraTracking {
+    /// Moved by the user only.
+    Free,
+    /// Attached behind the latest keyframe.
+    Follow,
+    /// Looks through the latest keyframe, with its intrinsics if any.
+    ThroughKeyFrame,
+}
+
+impl Default for CameraTracking {
+    fn default() -> Self {
+        Self::Free
+    }
+}
+
+impl CameraTracking {
+    /// The next mode, to cycle them with a key.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Free => Self::Follow,
+            Self::Follow => Self::ThroughKeyFrame,
+            Self::ThroughKeyFrame => Self::Free,
+        }
+    }
+}
+
+/// The latest keyframe of a renderer, as a camera pose in the world.
+///
+/// As the isometries are drawn, the camera looks along `+z` with `y` down.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KeyFrameView {
+    pub pose: Isometry3<f32>,
+    pub intrinsics: Option<CameraIntrinsics>,
+}
+
+impl KeyFrameView {
+    pub fn new<N>(pose: &Isometry3<N>, intrinsics: Option<CameraIntrinsics>) -> Self
+    where
+        N: 'static + Number,
+    {
+        let cast = |v: N| num::cast::<N, f32>(v).unwrap();
+
+        let rotation = pose.rotation.coords.map(cast);
+        Self {
+            pose: Isometry3::from_parts(
+                pose.translation.vector.map(cast).into(),
+                UnitQuaternion::new_normalize(Quaternion::from(rotation)),
+            ),
+            intrinsics,
+        }
+    }
+
+    /// The view transform, from `x` right, `y` down, `z` forward to `y` up, `z` backward.
+    pub fn view(&self) -> Matrix4<f32> {
+        let flip = Matrix4::from_diagonal(&Vector4::new(1.0, -1.0, -1.0, 1.0));
+        flip * self.pose.inverse().to_homogeneous()
+    }
+
+    pub fn view_proj(&self, aspect: f32, fovy: f32, znear: f32, zfar: f32) -> Matrix4<f32> {
+        let view = self.view();
+
+        let proj = match &self.intrinsics {
+            Some(intrinsics) => intrinsics.projection(znear, zfar),
+            None => Perspective3::new(aspect, fovy, znear, zfar).to_homogeneous(),
+        };
+        proj * view
+    }
+
+    /// Maps a point from the keyframe into the world.
+    pub fn transform(&self, point: &Point3<f32>) -> Point3<f32> {
+        self.pose * point
+    }
+}
@@ -0,0 +1,52 @@
+use slam_cv::Number;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Projection {
+    Perspective,
+    /// Parallel lines stay parallel; the scroll changes the scale instead of moving.
+    Orthographic,
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        Self::Perspective
+    }
+}
+
+/// A standard direction to look at the pivot from, like the numpad of Blender.
+///
+/// The axes below are those of the `y` up convention, turned with the up axis.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ViewPreset {
+    /// From above, down the up axis.
+    Top,
+    /// From the front, along `-z`.
+    Front,
+    /// From the right, along `-x`.
+    Side,
+    /// From above the front right corner.
+    Isometric,
+}
+
+impl ViewPreset {
+    /// The `(yaw, pitch)` of the camera, as in `Camera`.
+    pub fn orientation<N>(self) -> (N, N)
+    where
+        N: Number,
+    {
+        use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+        // looking straight down is not allowed by the camera
+        const TOP_PITCH: f64 = PI - 0.011;
+        // the elevation of the diagonal of a cube
+        const ISOMETRIC_PITCH: f64 = FRAC_PI_2 + 0.615_479_708_670_387_3;
+
+        let (yaw, pitch) = match self {
+            Self::Top => (-FRAC_PI_2, TOP_PITCH),
+            Self::Front => (-FRAC_PI_2, FRAC_PI_2),
+            Self::Side => (PI, FRAC_PI_2),
+            Self::Isometric => (-3.0 * FRAC_PI_4, ISOMETRIC_PITCH),
+        };
+        (N::from(yaw).unwrap(), N::from(pitch).unwrap())
+    }
+}
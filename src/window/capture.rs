@@ -0,0 +1,367 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+use crate::pipes::{DepthTexture, LayerChange};
+
+use failure::{Error, Fallible, ResultExt};
+use futures::FutureExt;
+use image::RgbaImage;
+
+/// `bytes_per_row` of a texture copy should be a multiple of this.
+const BYTES_PER_ROW_ALIGNMENT: u32 = 256;
+
+/// A texture which can be rendered like a swap chain frame, then read back.
+pub struct OffscreenTarget {
+    width: u32,
+    height: u32,
+    format: wgpu::TextureFormat,
+
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    depth_texture: DepthTexture,
+    output_buffer: wgpu::Buffer,
+}
+
+impl OffscreenTarget {
+    pub fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen_texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth: 1,
+            },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+        });
+        let view = texture.create_default_view();
+        let depth_texture = DepthTexture::new(device, width, height);
+
+        let output_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("offscreen_output_buffer"),
+            size: (padded_bytes_per_row(width) * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsage::COPY_DST | wgpu::BufferUsage::MAP_READ,
+        });
+
+        Self {
+            width,
+            height,
+            format,
+
+            texture,
+            view,
+            depth_texture,
+            output_buffer,
+        }
+    }
+
+    pub fn is_compatible(&self, width: u32, height: u32, format: wgpu::TextureFormat) -> bool {
+        self.width == width && self.height == height && self.format == format
+    }
+
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn depth_texture(&self) -> &DepthTexture {
+        &self.depth_texture
+    }
+
+    /// Records the copy of the rendered texture, to be read by `read`.
+    pub fn copy_to_buffer(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.copy_texture_to_buffer(
+            wgpu::TextureCopyView {
+                texture: &self.texture,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            wgpu::BufferCopyView {
+                buffer: &self.output_buffer,
+                offset: 0,
+                bytes_per_row: padded_bytes_per_row(self.width),
+                rows_per_image: self.height,
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth: 1,
+            },
+        );
+    }
+
+    /// Blocks until the copied texture is readable.
+    ///
+    /// This polls the mapping by hand, so it can be called inside the event loop as well.
+    /// It waits for the GPU to finish the frame, so the window stalls for each captured frame.
+    pub fn read(&self, device: &wgpu::Device) -> RgbaImage {
+        let bytes_per_row = padded_bytes_per_row(self.width);
+        let size = (bytes_per_row * self.height) as wgpu::BufferAddress;
+
+        let mapping = self.output_buffer.map_read(0, size);
+        device.poll(wgpu::Maintain::Wait);
+
+        let mapping = mapping
+            .now_or_never()
+            .expect("the device has been waited")
+            .expect("failed to read the rendered frame");
+        let data = mapping.as_slice();
+
+        let is_bgra = matches!(
+            self.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+
+        // remove the row padding
+        let row_size = (self.width * 4) as usize;
+        let mut pixels: Vec<u8> = data
+            .chunks(bytes_per_row as usize)
+            .flat_map(|row| row[..row_size].iter().cloned())
+            .collect();
+
+        if is_bgra {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        RgbaImage::from_raw(self.width, self.height, pixels).unwrap()
+    }
+}
+
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let bytes_per_row = width * 4;
+    let padding = (BYTES_PER_ROW_ALIGNMENT - bytes_per_row % BYTES_PER_ROW_ALIGNMENT)
+        % BYTES_PER_ROW_ALIGNMENT;
+    bytes_per_row + padding
+}
+
+/// A request sent to every window of a running viewer.
+#[derive(Clone, Debug)]
+pub enum WindowCommand {
+    /// Saves the next frame as a PNG file.
+    Screenshot(PathBuf),
+    /// Saves every frame into the directory, as `frame_000000.png`, ...
+    StartRecording(PathBuf),
+    StopRecording,
+    /// Changes the layers of the name, in the windows which have them.
+    Layer(String, LayerChange),
+}
+
+impl WindowCommand {
+    /// The command for the window of the index, among `count` windows.
+    ///
+    /// With several windows, the index is appended to the files so that they do not overwrite
+    /// each other, e.g. `map_0.png` and `map_1.png`.
+    pub fn for_window(&self, index: usize, count: usize) -> Self {
+        match self {
+            Self::Screenshot(path) if count > 1 => Self::Screenshot(with_suffix(path, index)),
+            Self::StartRecording(directory) if count > 1 => {
+                Self::StartRecording(with_suffix(directory, index))
+            }
+            command => command.clone(),
+        }
+    }
+}
+
+fn with_suffix(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}_{}.{}", stem, index, extension.to_string_lossy()),
+        None => format!("{}_{}", stem, index),
+    };
+    path.with_file_name(name)
+}
+
+struct Recording {
+    directory: PathBuf,
+    frame: usize,
+}
+
+/// A captured frame, and the files to save it into.
+struct Job {
+    image: RgbaImage,
+    paths: Vec<PathBuf>,
+}
+
+impl Job {
+    fn save(self, errors: &mpsc::Sender<Error>) {
+        for path in &self.paths {
+            let result = self
+                .image
+                .save(path)
+                .with_context(|_| format!("failed to save {}", path.display()));
+            if let Err(e) = result {
+                errors.send(e.into()).ok();
+            }
+        }
+    }
+}
+
+pub struct Capture {
+    target: Option<OffscreenTarget>,
+
+    screenshot: Option<PathBuf>,
+    recording: Option<Recording>,
+
+    /// The frames saved by the workers, created with the first captured frame.
+    #[cfg(not(target_arch = "wasm32"))]
+    jobs: Option<mpsc::SyncSender<Job>>,
+    error_sender: mpsc::Sender<Error>,
+    errors: mpsc::Receiver<Error>,
+}
+
+impl Default for Capture {
+    fn default() -> Self {
+        let (error_sender, errors) = mpsc::channel();
+
+        Self {
+            target: None,
+
+            screenshot: None,
+            recording: None,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            jobs: None,
+            error_sender,
+            errors,
+        }
+    }
+}
+
+impl Capture {
+    pub fn command(&mut self, command: WindowCommand) -> Fallible<()> {
+        match command {
+            WindowCommand::Screenshot(path) => self.screenshot = Some(path),
+            WindowCommand::StartRecording(directory) => {
+                std::fs::create_dir_all(&directory)
+                    .with_context(|_| format!("failed to create {}", directory.display()))?;
+                self.recording = Some(Recording {
+                    directory,
+                    frame: 0,
+                });
+            }
+            WindowCommand::StopRecording => self.recording = None,
+            // the layers are changed by the window
+            WindowCommand::Layer(..) => {}
+        }
+        Ok(())
+    }
+
+    /// Saves a screenshot into the working directory, not overwriting the others.
+    pub fn screenshot(&mut self) -> Fallible<()> {
+        let path = next_free_path(|i| PathBuf::from(format!("screenshot_{:04}.png", i)));
+        self.command(WindowCommand::Screenshot(path))
+    }
+
+    /// Starts a recording into a new directory, or stops the current one.
+    pub fn toggle_recording(&mut self) -> Fallible<()> {
+        if self.recording.is_some() {
+            self.command(WindowCommand::StopRecording)
+        } else {
+            let directory = next_free_path(|i| PathBuf::from(format!("recording_{:04}", i)));
+            self.command(WindowCommand::StartRecording(directory))
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.screenshot.is_some() || self.recording.is_some()
+    }
+
+    /// Returns a target of the given size, reusing the previous one if possible.
+    pub fn target(
+        &mut self,
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> &OffscreenTarget {
+        match &self.target {
+            Some(target) if target.is_compatible(width, height, format) => {}
+            _ => self.target = Some(OffscreenTarget::new(device, width, height, format)),
+        }
+        self.target.as_ref().unwrap()
+    }
+
+    /// Writes the captured frame into the requested files, in the background.
+    ///
+    /// If the workers fall behind, e.g. while recording, this blocks until a frame is saved,
+    /// so that the frames waiting in memory are bounded.
+    /// The failures are received later by `errors`.
+    pub fn save(&mut self, image: RgbaImage) {
+        let mut paths = vec![];
+        if let Some(path) = self.screenshot.take() {
+            paths.push(path);
+        }
+
+        if let Some(recording) = &mut self.recording {
+            let path = recording
+                .directory
+                .join(format!("frame_{:06}.png", recording.frame));
+            paths.push(path);
+            recording.frame += 1;
+        }
+
+        let job = Job { image, paths };
+
+        // the web has neither threads nor files to wait for
+        #[cfg(target_arch = "wasm32")]
+        job.save(&self.error_sender);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let error_sender = &self.error_sender;
+            let jobs = self.jobs.get_or_insert_with(|| spawn_workers(error_sender));
+            jobs.send(job).ok();
+        }
+    }
+
+    /// The failures to save the frames since the last call.
+    pub fn errors(&self) -> Vec<Error> {
+        self.errors.try_iter().collect()
+    }
+}
+
+/// Spawns the threads saving the jobs, which stop once the capture is dropped.
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_workers(errors: &mpsc::Sender<Error>) -> mpsc::SyncSender<Job> {
+    use std::sync::{Arc, Mutex};
+
+    /// Enough for a recording to keep up with the window, as encoding a PNG is slow.
+    const WORKERS: usize = 4;
+    /// The frames waiting for a worker, beyond which the window waits instead.
+    const QUEUED_JOBS: usize = 2 * WORKERS;
+
+    let (jobs, receiver) = mpsc::sync_channel::<Job>(QUEUED_JOBS);
+    let receiver = Arc::new(Mutex::new(receiver));
+
+    for _ in 0..WORKERS {
+        let receiver = receiver.clone();
+        let errors = errors.clone();
+        std::thread::spawn(move || loop {
+            // the lock is released before saving, so that the others take the next jobs
+            let job = receiver.lock().unwrap().recv();
+            match job {
+                Ok(job) => job.save(&errors),
+                Err(_) => break,
+            }
+        });
+    }
+    jobs
+}
+
+fn next_free_path<F>(f: F) -> PathBuf
+where
+    F: Fn(usize) -> PathBuf,
+{
+    (0..).map(f).find(|path| !path.exists()).unwrap()
+}
@@ -0,0 +1,183 @@
+/// Maps a scalar attribute (observation count, reprojection error, ...) to a color.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Colormap {
+    Viridis,
+    Jet,
+    Turbo,
+}
+
+impl Colormap {
+    /// Maps `t` in `[0, 1]` to a color. Out-of-range values are clamped.
+    pub fn map(self, t: f32) -> [f32; 3] {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+
+        match self {
+            Self::Viridis => viridis(t),
+            Self::Jet => jet(t),
+            Self::Turbo => turbo(t),
+        }
+    }
+
+    /// Maps `value` linearly from `[min, max]` to a color.
+    pub fn map_range(self, value: f32, min: f32, max: f32) -> [f32; 3] {
+        let range = max - min;
+        if range > 0.0 {
+            self.map((value - min) / range)
+        } else {
+            self.map(0.5)
+        }
+    }
+
+    /// Maps all the values, spreading them between their minimum and maximum.
+    pub fn map_values(self, values: &[f32]) -> Vec<[f32; 3]> {
+        let min = values.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+        values
+            .iter()
+            .map(|&v| self.map_range(v, min, max))
+            .collect()
+    }
+}
+
+/// Polynomial fit by Matt Zucker: https://www.shadertoy.com/view/WlfXRN
+fn viridis(t: f32) -> [f32; 3] {
+    const C: [[f32; 3]; 7] = [
+        [0.277_727_33, 0.005_407_344_5, 0.334_099_8],
+        [0.105_093_04, 1.404_613_5, 1.384_590_2],
+        [-0.330_861_83, 0.214_847_56, 0.095_095_16],
+        [-4.634_230_6, -5.799_101, -19.332_441],
+        [6.228_27, 14.179_933, 56.690_55],
+        [4.776_385, -13.745_145, -65.353_035],
+        [-5.435_456, 4.645_852_6, 26.312_435],
+    ];
+
+    let mut color = [0.0; 3];
+    for (i, c) in color.iter_mut().enumerate() {
+        *c = C.iter().rev().fold(0.0, |acc, coeff| acc * t + coeff[i]);
+    }
+    color
+}
+
+fn jet(t: f32) -> [f32; 3] {
+    let channel = |offset: f32| (1.5 - (4.0 * t - offset).abs()).clamp(0.0, 1.0);
+    [channel(3.0), channel(2.0), channel(1.0)]
+}
+
+/// Polynomial fit by Anton Mikhailov:
+/// https://ai.googleblog.com/2019/08/turbo-improved-rainbow-colormap-for.html
+fn turbo(t: f32) -> [f32; 3] {
+    const C: [[f32; 6]; 3] = [
+        [
+            0.135_721_38,
+            4.615_392_6,
+            -42.660_324,
+            132.131_08,
+            -152.942_4,
+            59.286_38,
+        ],
+        [
+            0.091_402_61,
+            2.194_188_4,
+            4.842_966_6,
+            -14.185_033,
+            4.277_298_5,
+            2.829_566,
+        ],
+        [
+            0.106_673_3,
+            12.641_946,
+            -60.582_05,
+            110.362_77,
+            -89.903_11,
+            27.348_25,
+        ],
+    ];
+
+    let mut color = [0.0; 3];
+    for (c, coeffs) in color.iter_mut().zip(C.iter()) {
+        let value: f32 = coeffs.iter().rev().fold(0.0, |acc, coeff| acc * t + coeff);
+        *c = value.clamp(0.0, 1.0);
+    }
+    color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLORMAPS: [Colormap; 3] = [Colormap::Viridis, Colormap::Jet, Colormap::Turbo];
+
+    fn assert_close(a: [f32; 3], b: [f32; 3], tolerance: f32) {
+        for (a, b) in a.iter().zip(&b) {
+            assert!((a - b).abs() < tolerance, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn endpoints() {
+        // the polynomial fits stay close to the published tables
+        assert_close(Colormap::Viridis.map(0.0), [0.267, 0.005, 0.329], 0.02);
+        assert_close(Colormap::Viridis.map(1.0), [0.993, 0.906, 0.144], 0.02);
+        assert_close(Colormap::Turbo.map(0.0), [0.190, 0.072, 0.232], 0.15);
+        assert_close(Colormap::Turbo.map(1.0), [0.480, 0.016, 0.011], 0.1);
+        assert_eq!(Colormap::Jet.map(0.0), [0.0, 0.0, 0.5]);
+        assert_eq!(Colormap::Jet.map(1.0), [0.5, 0.0, 0.0]);
+
+        for &colormap in &COLORMAPS {
+            for i in 0..=100 {
+                let color = colormap.map(i as f32 / 100.0);
+                assert!(color.iter().all(|c| (0.0..=1.0).contains(c)), "{:?}", color);
+            }
+        }
+    }
+
+    #[test]
+    fn clamped() {
+        for &colormap in &COLORMAPS {
+            assert_eq!(colormap.map(-1.0), colormap.map(0.0));
+            assert_eq!(colormap.map(2.0), colormap.map(1.0));
+            assert_eq!(colormap.map_range(-5.0, 0.0, 10.0), colormap.map(0.0));
+            assert_eq!(colormap.map_range(15.0, 0.0, 10.0), colormap.map(1.0));
+        }
+        assert_eq!(
+            Colormap::Jet.map_range(2.5, 0.0, 10.0),
+            Colormap::Jet.map(0.25)
+        );
+    }
+
+    #[test]
+    fn degenerate_range() {
+        let middle = Colormap::Turbo.map(0.5);
+        assert_eq!(Colormap::Turbo.map_range(1.0, 1.0, 1.0), middle);
+        assert_eq!(Colormap::Turbo.map_range(3.0, 1.0, 1.0), middle);
+        // a reversed range is degenerate too
+        assert_eq!(Colormap::Turbo.map_range(1.5, 2.0, 1.0), middle);
+
+        assert_eq!(Colormap::Turbo.map_values(&[4.0, 4.0]), vec![middle; 2]);
+        assert!(Colormap::Turbo.map_values(&[]).is_empty());
+    }
+
+    #[test]
+    fn nan() {
+        let low = Colormap::Viridis.map(0.0);
+        assert_eq!(Colormap::Viridis.map(f32::NAN), low);
+        assert_eq!(Colormap::Viridis.map_range(f32::NAN, 0.0, 1.0), low);
+        assert_eq!(
+            Colormap::Viridis.map_range(0.3, f32::NAN, 1.0),
+            Colormap::Viridis.map(0.5)
+        );
+
+        // a NaN value does not change the range of the others
+        let colors = Colormap::Viridis.map_values(&[0.0, f32::NAN, 2.0, 1.0]);
+        assert_eq!(
+            colors,
+            vec![
+                low,
+                low,
+                Colormap::Viridis.map(1.0),
+                Colormap::Viridis.map(0.5)
+            ]
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use super::viewport::ViewportRect;
+use crate::pipes::{DepthTexture, PipelineRenderer};
+
+use failure::{err_msg, Fallible};
+
+/// Fails if the machine has no adapter, e.g. a CI runner without any GPU or software driver.
+pub async fn request_device(
+    instance: &wgpu::Instance,
+    compatible_surface: Option<&wgpu::Surface>,
+    backends: wgpu::BackendBit,
+) -> Fallible<(wgpu::Device, wgpu::Queue)> {
+    let adapter = instance
+        .request_adapter(
+            &wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::Default,
+                compatible_surface,
+            },
+            backends,
+        )
+        .await
+        .ok_or_else(|| err_msg("failed to find an appropriate adapter"))?;
+
+    adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                extensions: wgpu::Extensions {
+                    anisotropic_filtering: false,
+                },
+                limits: Default::default(),
+            },
+            None,
+        )
+        .await
+        .map_err(|_| err_msg("failed to create a device"))
+}
+
+/// Encodes a render pass drawing the pipelines on the target, in order.
+pub fn encode_render_pass(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    target: &wgpu::TextureView,
+    depth_texture: &DepthTexture,
+    uniform_bind_group: &wgpu::BindGroup,
+    pipeline_rendeners: &mut [&mut dyn PipelineRenderer],
+) -> wgpu::CommandEncoder {
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Render Encoder"),
+    });
+
+    for pipeline_rendener in pipeline_rendeners.iter_mut() {
+        pipeline_rendener.prepare(device, queue);
+    }
+    render_pass(
+        &mut encoder,
+        device,
+        queue,
+        RenderTarget {
+            view: target,
+            depth_texture,
+            clear: true,
+            viewport: None,
+        },
+        uniform_bind_group,
+        pipeline_rendeners,
+    );
+
+    encoder
+}
+
+/// Where a render pass draws.
+pub struct RenderTarget<'a> {
+    pub view: &'a wgpu::TextureView,
+    pub depth_texture: &'a DepthTexture,
+    /// Whether the target is cleared first, or drawn over.
+    pub clear: bool,
+    /// The part of the target drawn, or all of it.
+    pub viewport: Option<ViewportRect>,
+}
+
+/// Records a render pass drawing the pipelines on the target, in order,
+/// then their overlays in the same order.
+pub fn render_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    target: RenderTarget<'_>,
+    uniform_bind_group: &wgpu::BindGroup,
+    pipeline_rendeners: &mut [&mut dyn PipelineRenderer],
+) {
+    let load_op = if target.clear {
+        wgpu::LoadOp::Clear
+    } else {
+        wgpu::LoadOp::Load
+    };
+
+    let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+            attachment: target.view,
+            resolve_target: None,
+            load_op,
+            store_op: wgpu::StoreOp::Store,
+            clear_color: wgpu::Color::BLACK,
+        }],
+        depth_stencil_attachment: Some(target.depth_texture.attachment(load_op)),
+    });
+
+    if let Some(rect) = target.viewport {
+        let ViewportRect {
+            x,
+            y,
+            width,
+            height,
+        } = rect;
+        render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+        render_pass.set_scissor_rect(x, y, width, height);
+    }
+
+    for pipeline_rendener in pipeline_rendeners.iter() {
+        // a pipeline may bind its own uniforms, e.g. the axis gizmo
+        render_pass.set_bind_group(0, uniform_bind_group, &[]);
+        pipeline_rendener.render(device, queue, &mut render_pass);
+    }
+
+    // the overlays of all the pipelines last, as they are painted over by anything drawn after
+    for pipeline_rendener in pipeline_rendeners.iter() {
+        render_pass.set_bind_group(0, uniform_bind_group, &[]);
+        pipeline_rendener.render_overlay(device, queue, &mut render_pass);
+    }
+}
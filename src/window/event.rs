@@ -2,4 +2,6 @@
 pub enum WindowEventState {
     Consumed,
     Unused,
+    /// The input asks to close the viewer.
+    Exit,
 }
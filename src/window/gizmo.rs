@@ -0,0 +1,131 @@
+use super::camera::Camera;
+use super::lines::{build_render_pipeline, Line};
+use super::points::Point;
+use super::uniform::{create_bind_group_layout, UniformBuffer, Uniforms};
+use crate::pipes::{GpuVec, PipelineRenderer, VertexFormat};
+
+use nalgebra::{Matrix4, Point3};
+use slam_cv::{Colors, Number};
+
+/// The length of the axes, in pixels.
+const SIZE: f32 = 40.0;
+/// The distance of the origin from the bottom left corner, in pixels.
+const MARGIN: f32 = 60.0;
+
+/// The world axes (x red, y green, z blue), turned with the camera in the corner.
+pub struct AxisGizmo<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    render_pipeline: wgpu::RenderPipeline,
+    buffer: GpuVec<Line<N>>,
+
+    uniforms: UniformBuffer<N>,
+}
+
+impl<N> AxisGizmo<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    pub fn new(device: &wgpu::Device, texture_format: wgpu::TextureFormat) -> Self {
+        let bind_group_layout = create_bind_group_layout(device);
+        let uniforms = UniformBuffer::new(device, &bind_group_layout, Uniforms::default());
+
+        let render_pipeline =
+            build_render_pipeline(device, texture_format, &bind_group_layout, false);
+
+        let origin = Point3::origin();
+        let axis = |end: Point3<N>, color: [f32; 3]| Line {
+            start: Point {
+                position: origin,
+                color: color.into(),
+            },
+            end: Point {
+                position: end,
+                color: color.into(),
+            },
+        };
+
+        let (zero, one) = (N::zero(), N::one());
+        let mut buffer = GpuVec::new(wgpu::BufferUsage::VERTEX);
+        buffer.update(vec![
+            axis(Point3::new(one, zero, zero), Colors::red()),
+            axis(Point3::new(zero, one, zero), Colors::green()),
+            axis(Point3::new(zero, zero, one), Colors::blue()),
+        ]);
+
+        Self {
+            render_pipeline,
+            buffer,
+
+            uniforms,
+        }
+    }
+
+    /// Turns the axes like the world seen by the camera.
+    pub fn update(&mut self, queue: &wgpu::Queue, camera: &Camera<N>, width: u32, height: u32) {
+        let cast = |v: f32| N::from(v).unwrap();
+        let (zero, one) = (N::zero(), N::one());
+        let width = cast(width.max(1) as f32);
+        let height = cast(height.max(1) as f32);
+        let two = one + one;
+
+        let mut rotation = camera.view_matrix();
+        rotation[(0, 3)] = zero;
+        rotation[(1, 3)] = zero;
+        rotation[(2, 3)] = zero;
+
+        // from the view to the corner, keeping the depth inside the clip space
+        let size = cast(SIZE);
+        let margin = cast(MARGIN);
+        let half = cast(0.5);
+        let corner = Matrix4::new(
+            two * size / width,
+            zero,
+            zero,
+            two * margin / width - one,
+            //
+            zero,
+            two * size / height,
+            zero,
+            two * margin / height - one,
+            //
+            zero,
+            zero,
+            cast(0.25),
+            half,
+            //
+            zero,
+            zero,
+            zero,
+            one,
+        );
+
+        self.uniforms.uniforms.set_view_proj(corner * rotation);
+        self.uniforms.uniforms.update_viewport(width, height);
+        self.uniforms.upload(queue);
+    }
+}
+
+impl<N> PipelineRenderer for AxisGizmo<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.buffer.upload(device, queue);
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.uniforms.bind_group, &[]);
+        self.buffer.set_buffer(render_pass);
+    }
+}
@@ -0,0 +1,124 @@
+use super::builder::WindowBuilder;
+use super::camera::{Camera, CameraController};
+use super::capture::OffscreenTarget;
+use super::context::{encode_render_pass, request_device};
+use super::gizmo::AxisGizmo;
+use super::uniform::{create_bind_group_layout, UniformBuffer, Uniforms};
+use crate::pipes::{LayerChange, PipelineBuilder, PipelineRenderer, VertexFormat};
+
+use failure::Fallible;
+use image::RgbaImage;
+use nalgebra::Point3;
+use slam_cv::Number;
+
+const TEXTURE_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Renders the same pipelines as a `Window` into a texture, without any surface.
+///
+/// Only the camera of the window is drawn, over the whole image; its `layout` is not used.
+pub struct HeadlessWindow<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+
+    width: u32,
+    height: u32,
+    target: OffscreenTarget,
+
+    pipeline_rendener: Box<dyn PipelineRenderer>,
+    gizmo: AxisGizmo<N>,
+
+    pub camera: Camera<N>,
+    camera_controller: CameraController<N>,
+
+    uniforms: UniformBuffer<N>,
+}
+
+impl<N> HeadlessWindow<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    /// Fails if there is no adapter to render with, or if a change of the builder names no layer.
+    pub async fn new(
+        width: u32,
+        height: u32,
+        builder: WindowBuilder<N>,
+        pipeline_builder: Box<dyn PipelineBuilder<N>>,
+    ) -> Fallible<Self> {
+        let instance = wgpu::Instance::new();
+
+        // any backend, so that software adapters (e.g. lavapipe, SwiftShader) are accepted
+        let (device, queue) = request_device(&instance, None, wgpu::BackendBit::all()).await?;
+
+        let target = OffscreenTarget::new(&device, width, height, TEXTURE_FORMAT);
+
+        let camera = builder.camera.into();
+        let camera_controller = builder.camera_controller.into();
+
+        let bind_group_layout = create_bind_group_layout(&device);
+        let uniforms = UniformBuffer::new(&device, &bind_group_layout, Uniforms::default());
+
+        let mut pipeline_rendener =
+            pipeline_builder.build(&device, TEXTURE_FORMAT, &bind_group_layout);
+        LayerChange::apply_all(&builder.layers, pipeline_rendener.as_mut())?;
+        let gizmo = AxisGizmo::new(&device, TEXTURE_FORMAT);
+
+        Ok(Self {
+            device,
+            queue,
+
+            width,
+            height,
+            target,
+
+            pipeline_rendener,
+            gizmo,
+
+            camera,
+            camera_controller,
+
+            uniforms,
+        })
+    }
+
+    /// Renders a frame with the current camera and reads it back.
+    ///
+    /// This blocks until the frame has been rendered.
+    pub fn render(&mut self) -> RgbaImage {
+        self.pipeline_rendener.sync();
+
+        if self.camera_controller.is_fit_pending() {
+            // the bounds are known once the data has been drawn
+            self.draw();
+
+            let bounds = self.pipeline_rendener.bounds();
+            self.camera_controller.fit_bounds(&mut self.camera, bounds);
+        }
+        self.draw();
+
+        self.target.read(&self.device)
+    }
+
+    fn draw(&mut self) {
+        self.uniforms.update(&self.camera, self.width, self.height);
+        self.uniforms.upload(&self.queue);
+        self.gizmo
+            .update(&self.queue, &self.camera, self.width, self.height);
+
+        let mut encoder = encode_render_pass(
+            &self.device,
+            &self.queue,
+            self.target.view(),
+            self.target.depth_texture(),
+            &self.uniforms.bind_group,
+            &mut [self.pipeline_rendener.as_mut(), &mut self.gizmo],
+        );
+        self.target.copy_to_buffer(&mut encoder);
+
+        self.queue.submit(Some(encoder.finish()));
+    }
+}
@@ -0,0 +1,85 @@
+use super::lines::{build_render_pipeline, Line};
+use super::points::Point;
+use crate::pipes::{GpuVec, Pick, PipelineRenderer, VertexFormat};
+
+use nalgebra::{Point3, Vector3};
+use slam_cv::Number;
+
+const COLOR: [f32; 3] = [1.0, 1.0, 0.0];
+
+/// A cross over the picked data, drawn in front of everything.
+pub struct Highlight<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    render_pipeline: wgpu::RenderPipeline,
+    buffer: GpuVec<Line<N>>,
+}
+
+impl<N> Highlight<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    pub fn new(
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Self {
+        let render_pipeline =
+            build_render_pipeline(device, texture_format, uniform_bind_group_layout, false);
+
+        Self {
+            render_pipeline,
+            buffer: GpuVec::new(wgpu::BufferUsage::VERTEX),
+        }
+    }
+
+    /// Marks the pick with a cross of the half size `size`, or clears the mark.
+    pub fn set(&mut self, pick: Option<&Pick>, size: f32) {
+        let pick = match pick {
+            Some(pick) => pick,
+            None => {
+                self.buffer.clear();
+                return;
+            }
+        };
+
+        let cast = |p: Point3<f32>| p.map(|v| N::from(v).unwrap());
+        let point = |position| Point {
+            position: cast(position),
+            color: COLOR.into(),
+        };
+        let axis = |axis: Vector3<f32>| Line {
+            start: point(pick.position - axis * size),
+            end: point(pick.position + axis * size),
+        };
+
+        self.buffer.update(vec![
+            axis(Vector3::x()),
+            axis(Vector3::y()),
+            axis(Vector3::z()),
+        ]);
+    }
+}
+
+impl<N> PipelineRenderer for Highlight<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.buffer.upload(device, queue);
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        render_pass.set_pipeline(&self.render_pipeline);
+        self.buffer.set_buffer(render_pass);
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashMap;
+
+use super::camera::ViewPreset;
+use crate::pipes::Pick;
+
+use winit::event::{ElementState, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent};
+
+/// A key or a mouse button.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Input {
+    Key(VirtualKeyCode),
+    Mouse(MouseButton),
+}
+
+impl From<VirtualKeyCode> for Input {
+    fn from(key: VirtualKeyCode) -> Self {
+        Self::Key(key)
+    }
+}
+
+impl From<MouseButton> for Input {
+    fn from(button: MouseButton) -> Self {
+        Self::Mouse(button)
+    }
+}
+
+/// What an input does to the window.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Turns the camera while held, with the cursor.
+    Rotate,
+    /// Moves the camera while held, with the cursor.
+    Pan,
+    MoveLeft,
+    MoveRight,
+    MoveForward,
+    MoveBackward,
+    CycleTracking,
+    FrameAll,
+    ToggleProjection,
+    Preset(ViewPreset),
+    /// Picks the data under the cursor; a click without a drag picks as well.
+    Pick,
+    /// Shows or hides the layer of the index, from 0, as numbered from 1 in the legend.
+    ToggleLayer(usize),
+    ToggleLegend,
+    /// Shows or hides the debug overlay.
+    ToggleOverlay,
+    Screenshot,
+    ToggleRecording,
+    /// Closes all the windows.
+    Quit,
+}
+
+/// A callback of an application, called when its input is pressed.
+pub type InputCallback = Box<dyn FnMut() + Send>;
+
+/// A callback of an application, called with the picked data.
+pub type PickCallback = Box<dyn FnMut(&Pick) + Send>;
+
+/// What became of an event in the `InputMap`.
+pub(crate) enum Mapped {
+    /// The action, and whether its input is pressed.
+    Action(Action, bool),
+    /// A callback was bound to the input.
+    Called,
+    Unbound,
+}
+
+/// Binds the keys and the mouse buttons to actions or to callbacks.
+pub struct InputMap {
+    actions: HashMap<Input, Action>,
+    callbacks: HashMap<Input, InputCallback>,
+    pick_callback: Option<PickCallback>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use VirtualKeyCode as Key;
+
+        let mut map = Self::empty();
+        map.bind(MouseButton::Left, Action::Rotate);
+        map.bind(MouseButton::Right, Action::Pan);
+        map.bind(MouseButton::Middle, Action::Pick);
+
+        map.bind(Key::A, Action::MoveLeft);
+        map.bind(Key::Left, Action::MoveLeft);
+        map.bind(Key::D, Action::MoveRight);
+        map.bind(Key::Right, Action::MoveRight);
+        map.bind(Key::W, Action::MoveForward);
+        map.bind(Key::Up, Action::MoveForward);
+        map.bind(Key::S, Action::MoveBackward);
+        map.bind(Key::Down, Action::MoveBackward);
+
+        map.bind(Key::T, Action::CycleTracking);
+        map.bind(Key::F, Action::FrameAll);
+        map.bind(Key::Numpad5, Action::ToggleProjection);
+        map.bind(Key::Numpad7, Action::Preset(ViewPreset::Top));
+        map.bind(Key::Numpad1, Action::Preset(ViewPreset::Front));
+        map.bind(Key::Numpad3, Action::Preset(ViewPreset::Side));
+        map.bind(Key::Numpad9, Action::Preset(ViewPreset::Isometric));
+
+        let digits = [
+            Key::Key1,
+            Key::Key2,
+            Key::Key3,
+            Key::Key4,
+            Key::Key5,
+            Key::Key6,
+            Key::Key7,
+            Key::Key8,
+            Key::Key9,
+        ];
+        for (index, &key) in digits.iter().enumerate() {
+            map.bind(key, Action::ToggleLayer(index));
+        }
+        map.bind(Key::L, Action::ToggleLegend);
+        map.bind(Key::F1, Action::ToggleOverlay);
+        map.bind(Key::F12, Action::Screenshot);
+        map.bind(Key::F10, Action::ToggleRecording);
+        map.bind(Key::Escape, Action::Quit);
+        map
+    }
+}
+
+impl InputMap {
+    /// Binds nothing.
+    pub fn empty() -> Self {
+        Self {
+            actions: HashMap::new(),
+            callbacks: HashMap::new(),
+            pick_callback: None,
+        }
+    }
+
+    /// Binds the input to the action, replacing its previous binding.
+    pub fn bind(&mut self, input: impl Into<Input>, action: Action) {
+        let input = input.into();
+        self.callbacks.remove(&input);
+        self.actions.insert(input, action);
+    }
+
+    /// Calls the callback when the input is pressed, replacing its previous binding.
+    pub fn bind_callback<F>(&mut self, input: impl Into<Input>, callback: F)
+    where
+        F: 'static + FnMut() + Send,
+    {
+        let input = input.into();
+        self.actions.remove(&input);
+        self.callbacks.insert(input, Box::new(callback));
+    }
+
+    /// Calls the callback with the data picked by a click.
+    pub fn bind_pick<F>(&mut self, callback: F)
+    where
+        F: 'static + FnMut(&Pick) + Send,
+    {
+        self.pick_callback = Some(Box::new(callback));
+    }
+
+    /// Removes the binding of the input, e.g. `Escape` to keep the window open.
+    pub fn unbind(&mut self, input: impl Into<Input>) {
+        let input = input.into();
+        self.actions.remove(&input);
+        self.callbacks.remove(&input);
+    }
+
+    /// Removes all the bindings of the action.
+    pub fn unbind_action(&mut self, action: Action) {
+        self.actions.retain(|_, a| *a != action);
+    }
+
+    pub(crate) fn picked(&mut self, pick: &Pick) {
+        if let Some(callback) = &mut self.pick_callback {
+            callback(pick);
+        }
+    }
+
+    /// The action of a pressed or released input, calling its callback if any.
+    pub(crate) fn map(&mut self, event: &WindowEvent) -> Mapped {
+        let (input, state) = match event {
+            WindowEvent::KeyboardInput {
+                input:
+                    KeyboardInput {
+                        state,
+                        virtual_keycode: Some(keycode),
+                        ..
+                    },
+                ..
+            } => (Input::Key(*keycode), state),
+            WindowEvent::MouseInput { state, button, .. } => (Input::Mouse(*button), state),
+            _ => return Mapped::Unbound,
+        };
+        let is_pressed = *state == ElementState::Pressed;
+
+        if let Some(callback) = self.callbacks.get_mut(&input) {
+            if is_pressed {
+                callback();
+            }
+            return Mapped::Called;
+        }
+        match self.actions.get(&input) {
+            Some(action) => Mapped::Action(*action, is_pressed),
+            None => Mapped::Unbound,
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use super::quad::ImageVertex;
+use crate::pipes::{depth_stencil_state, StaticShaderModule, VertexFormat};
+
+use image::{imageops::FilterType, RgbaImage};
+use nalgebra::Point3;
+use slam_cv::Number;
+
+const ATLAS_SIZE: u32 = 2048;
+/// The size the images are downscaled to, in pixels.
+const TILE_WIDTH: u32 = 128;
+const TILE_HEIGHT: u32 = 96;
+
+const COLUMNS: u32 = ATLAS_SIZE / TILE_WIDTH;
+const ROWS: u32 = ATLAS_SIZE / TILE_HEIGHT;
+
+/// The area of an image in the atlas, in texture coordinates.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tile {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+/// The downscaled images of the keyframes in a single texture, so that all of them are drawn
+/// at once.
+///
+/// When the atlas is full, the latest images take the tiles of the oldest ones.
+pub struct ImageAtlas {
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// Created with the first image.
+    texture: Option<(wgpu::Texture, wgpu::BindGroup)>,
+
+    /// The images in the tiles, to upload only the new ones.
+    tiles: Vec<Option<Arc<RgbaImage>>>,
+}
+
+impl ImageAtlas {
+    pub fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            bindings: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::SampledTexture {
+                        multisampled: false,
+                        dimension: wgpu::TextureViewDimension::D2,
+                        component_type: wgpu::TextureComponentType::Float,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler { comparison: false },
+                },
+            ],
+            label: Some("atlas_bind_group_layout"),
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 0.0,
+            compare: wgpu::CompareFunction::Undefined,
+        });
+
+        Self {
+            bind_group_layout,
+            sampler,
+            texture: None,
+
+            tiles: vec![None; (COLUMNS * ROWS) as usize],
+        }
+    }
+
+    pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+        &self.bind_group_layout
+    }
+
+    pub fn bind_group(&self) -> Option<&wgpu::BindGroup> {
+        self.texture.as_ref().map(|(_, bind_group)| bind_group)
+    }
+
+    /// Uploads the new images, in the order of the keyframes, returning their tiles.
+    ///
+    /// The keyframes without an image, or whose tile was taken, have none.
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        images: &[Option<Arc<RgbaImage>>],
+    ) -> Vec<Option<Tile>> {
+        let capacity = self.tiles.len();
+        let count = images.iter().filter(|image| image.is_some()).count();
+        let evicted = count.saturating_sub(capacity);
+
+        let mut rank = 0;
+        let mut tiles = Vec::with_capacity(images.len());
+        for image in images {
+            let tile = match image {
+                Some(image) => {
+                    rank += 1;
+                    if rank > evicted {
+                        let slot = (rank - 1) % capacity;
+                        self.upload(device, queue, slot, image);
+                        Some(tile(slot))
+                    } else {
+                        None
+                    }
+                }
+                None => None,
+            };
+            tiles.push(tile);
+        }
+        tiles
+    }
+
+    fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        slot: usize,
+        image: &Arc<RgbaImage>,
+    ) {
+        if let Some(uploaded) = &self.tiles[slot] {
+            if Arc::ptr_eq(uploaded, image) {
+                return;
+            }
+        }
+
+        let data = image::imageops::resize(&**image, TILE_WIDTH, TILE_HEIGHT, FilterType::Triangle);
+
+        let slot = slot as u32;
+        let (texture, _) = self.texture(device);
+        queue.write_texture(
+            wgpu::TextureCopyView {
+                texture,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d {
+                    x: slot % COLUMNS * TILE_WIDTH,
+                    y: slot / COLUMNS * TILE_HEIGHT,
+                    z: 0,
+                },
+            },
+            &data,
+            wgpu::TextureDataLayout {
+                offset: 0,
+                bytes_per_row: 4 * TILE_WIDTH,
+                rows_per_image: TILE_HEIGHT,
+            },
+            wgpu::Extent3d {
+                width: TILE_WIDTH,
+                height: TILE_HEIGHT,
+                depth: 1,
+            },
+        );
+
+        self.tiles[slot as usize] = Some(image.clone());
+    }
+
+    fn texture(&mut self, device: &wgpu::Device) -> &(wgpu::Texture, wgpu::BindGroup) {
+        let bind_group_layout = &self.bind_group_layout;
+        let sampler = &self.sampler;
+
+        self.texture.get_or_insert_with(|| {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("atlas_texture"),
+                size: wgpu::Extent3d {
+                    width: ATLAS_SIZE,
+                    height: ATLAS_SIZE,
+                    depth: 1,
+                },
+                array_layer_count: 1,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            });
+
+            let view = texture.create_default_view();
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: bind_group_layout,
+                bindings: &[
+                    wgpu::Binding {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::Binding {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(sampler),
+                    },
+                ],
+                label: Some("atlas_bind_group"),
+            });
+
+            (texture, bind_group)
+        })
+    }
+}
+
+/// The texture coordinates of the tile, half a texel inside so that the neighbors do not bleed.
+fn tile(slot: usize) -> Tile {
+    let slot = slot as u32;
+    let x = (slot % COLUMNS * TILE_WIDTH) as f32;
+    let y = (slot / COLUMNS * TILE_HEIGHT) as f32;
+    let size = ATLAS_SIZE as f32;
+
+    Tile {
+        min: [(x + 0.5) / size, (y + 0.5) / size],
+        max: [
+            (x + TILE_WIDTH as f32 - 0.5) / size,
+            (y + TILE_HEIGHT as f32 - 0.5) / size,
+        ],
+    }
+}
+
+pub fn build_image_pipeline<N>(
+    device: &wgpu::Device,
+    texture_format: wgpu::TextureFormat,
+    uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    atlas: &ImageAtlas,
+    depth_test: bool,
+) -> wgpu::RenderPipeline
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    const VS_SRC: StaticShaderModule = StaticShaderModule {
+        spirv_source: include_bytes!("image.vert.spv"),
+        entry_point: None,
+    };
+    const FS_SRC: StaticShaderModule = StaticShaderModule {
+        spirv_source: include_bytes!("image.frag.spv"),
+        entry_point: None,
+    };
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[&uniform_bind_group_layout, atlas.bind_group_layout()],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: &render_pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &VS_SRC.build(device),
+            entry_point: VS_SRC.entry_point(),
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &FS_SRC.build(device),
+            entry_point: FS_SRC.entry_point(),
+        }),
+        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            // the images are seen from behind as well
+            cull_mode: wgpu::CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: texture_format,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        depth_stencil_state: Some(depth_stencil_state(depth_test)),
+        vertex_state: wgpu::VertexStateDescriptor {
+            index_format: wgpu::IndexFormat::Uint16,
+            vertex_buffers: &[ImageVertex::<N>::desc(&ImageVertex::<N>::attributes())],
+        },
+        sample_count: 1,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
@@ -1,6 +1,7 @@
 use core::marker::PhantomData;
 
 use super::super::lines::build_render_pipeline;
+use super::atlas::{build_image_pipeline, ImageAtlas};
 use super::renderer::IsometriesRendener;
 use super::source::IsometrySource;
 use crate::pipes::{GpuVec, VertexFormat};
@@ -39,12 +40,36 @@ where
         texture_format: wgpu::TextureFormat,
         uniform_bind_group_layout: &wgpu::BindGroupLayout,
     ) -> IsometriesRendener<N, S> {
-        let render_pipeline =
-            build_render_pipeline(device, texture_format, uniform_bind_group_layout);
+        let render_pipeline = build_render_pipeline(
+            device,
+            texture_format,
+            uniform_bind_group_layout,
+            S::DEPTH_TEST,
+        );
+
+        let atlas = ImageAtlas::new(device);
+        let image_pipeline = build_image_pipeline::<N>(
+            device,
+            texture_format,
+            uniform_bind_group_layout,
+            &atlas,
+            S::DEPTH_TEST,
+        );
 
         IsometriesRendener {
             render_pipeline,
             buffer: GpuVec::new(wgpu::BufferUsage::VERTEX),
+            image_pipeline,
+            images: GpuVec::new(wgpu::BufferUsage::VERTEX),
+            atlas,
+            version: None,
+            name: self.source.name().to_string(),
+            latest: None,
+            pick_index: None,
+            visible: true,
+            color: None,
+            transform: None,
+            scale: 1.0,
 
             number: Default::default(),
             source: self.source,
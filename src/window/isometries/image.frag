@@ -0,0 +1,11 @@
+#version 450
+
+layout(location=0)in vec2 v_uv;
+layout(location=0)out vec4 f_color;
+
+layout(set=1,binding=0)uniform texture2D t_atlas;
+layout(set=1,binding=1)uniform sampler s_atlas;
+
+void main(){
+    f_color=texture(sampler2D(t_atlas,s_atlas),v_uv);
+}
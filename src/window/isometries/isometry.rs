@@ -2,9 +2,10 @@ use super::super::lines::Line;
 use super::super::points::Point;
 use crate::pipes::{GpuVertex, VertexFormat};
 
-use nalgebra::{Isometry3, Matrix4, Point2, Point3, Vector4};
+use nalgebra::{Isometry3, Point3};
 use slam_cv::Number;
 
+/// A keyframe drawn as the pyramid of its camera, from the optical center to the image plane.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Isometry<N>
@@ -12,7 +13,7 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
-    lines: [Line<N>; 6],
+    lines: [Line<N>; 8],
 }
 
 impl<N> Isometry<N>
@@ -20,88 +21,48 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
-    pub fn from_iso(iso: Isometry3<N>, camera_size: Point2<N>, color: Point3<f32>) -> Self {
-        Self::from_homogeneous(iso.to_homogeneous(), camera_size, color)
-    }
+    /// `image_plane` is in the frame of the camera, see `CameraIntrinsics::image_plane`.
+    pub fn from_iso(iso: Isometry3<N>, image_plane: &[Point3<N>; 4], color: Point3<f32>) -> Self {
+        let point = |position| Point { position, color };
+        let line = |start, end| Line {
+            start: point(start),
+            end: point(end),
+        };
 
-    pub fn from_homogeneous(iso: Matrix4<N>, camera_size: Point2<N>, color: Point3<f32>) -> Self {
-        let size = Point2::new(camera_size.x, camera_size.y);
-        let pose = iso;
+        let center = iso * Point3::origin();
+        let corners = [
+            iso * image_plane[0],
+            iso * image_plane[1],
+            iso * image_plane[2],
+            iso * image_plane[3],
+        ];
 
         Self {
             lines: [
-                Line {
-                    start: Point {
-                        position: map_pose(&pose, -size.x, -size.y),
-                        color,
-                    },
-                    end: Point {
-                        position: map_pose(&pose, size.x, size.y),
-                        color,
-                    },
-                },
-                Line {
-                    start: Point {
-                        position: map_pose(&pose, -size.x, size.y),
-                        color,
-                    },
-                    end: Point {
-                        position: map_pose(&pose, size.x, -size.y),
-                        color,
-                    },
-                },
-                Line {
-                    start: Point {
-                        position: map_pose(&pose, size.x, size.y),
-                        color,
-                    },
-                    end: Point {
-                        position: map_pose(&pose, size.x, -size.y),
-                        color,
-                    },
-                },
-                Line {
-                    start: Point {
-                        position: map_pose(&pose, -size.x, size.y),
-                        color,
-                    },
-                    end: Point {
-                        position: map_pose(&pose, -size.x, -size.y),
-                        color,
-                    },
-                },
-                Line {
-                    start: Point {
-                        position: map_pose(&pose, size.x, size.y),
-                        color,
-                    },
-                    end: Point {
-                        position: map_pose(&pose, -size.x, size.y),
-                        color,
-                    },
-                },
-                Line {
-                    start: Point {
-                        position: map_pose(&pose, size.x, -size.y),
-                        color,
-                    },
-                    end: Point {
-                        position: map_pose(&pose, -size.x, -size.y),
-                        color,
-                    },
-                },
+                line(center, corners[0]),
+                line(center, corners[1]),
+                line(center, corners[2]),
+                line(center, corners[3]),
+                line(corners[0], corners[1]),
+                line(corners[1], corners[2]),
+                line(corners[2], corners[3]),
+                line(corners[3], corners[0]),
             ],
         }
     }
-}
 
-fn map_pose<N>(pose: &Matrix4<N>, x: N, y: N) -> Point3<N>
-where
-    N: 'static + Number,
-    Point3<N>: VertexFormat<N>,
-{
-    let m = pose * Vector4::new(x, y, N::zero(), N::one());
-    Point3::new(m.x, m.y, m.z)
+    /// The optical center and the corners of the drawn frustum.
+    pub fn positions(&self) -> Vec<Point3<N>> {
+        self.lines
+            .iter()
+            .flat_map(|l| vec![l.start.position, l.end.position])
+            .collect()
+    }
+
+    /// The origin of the pose, i.e. the optical center.
+    pub fn center(&self) -> Point3<N> {
+        self.lines[0].start.position
+    }
 }
 
 impl<N> GpuVertex for Isometry<N>
@@ -110,7 +71,7 @@ where
     Point3<N>: VertexFormat<N>,
 {
     fn weight() -> u64 {
-        12
+        16
     }
 }
 
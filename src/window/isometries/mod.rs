@@ -2,7 +2,9 @@ mod builder;
 mod renderer;
 mod source;
 
+mod atlas;
 mod isometry;
+mod quad;
 
 pub use self::builder::IsometriesBuilder;
 pub use self::renderer::IsometriesRendener;
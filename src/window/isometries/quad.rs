@@ -0,0 +1,114 @@
+use core::mem;
+
+use super::atlas::Tile;
+use crate::pipes::{GpuVertex, VertexFormat};
+
+use nalgebra::{Isometry3, Point3};
+use slam_cv::Number;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ImageVertex<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    pub position: Point3<N>,
+    /// In the atlas, from its top left corner.
+    pub uv: [f32; 2],
+}
+
+type Attributes = [wgpu::VertexAttributeDescriptor; 2];
+
+impl<N> ImageVertex<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    pub fn attributes() -> Attributes {
+        [
+            wgpu::VertexAttributeDescriptor {
+                offset: 0,
+                shader_location: 0,
+                format: Point3::<N>::format(),
+            },
+            wgpu::VertexAttributeDescriptor {
+                offset: mem::size_of::<Point3<N>>() as wgpu::BufferAddress,
+                shader_location: 1,
+                format: wgpu::VertexFormat::Float2,
+            },
+        ]
+    }
+
+    pub fn desc(attributes: &Attributes) -> wgpu::VertexBufferDescriptor<'_> {
+        wgpu::VertexBufferDescriptor {
+            stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes,
+        }
+    }
+}
+
+/// The image of a keyframe, on the image plane of its frustum.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ImageQuad<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    vertices: [ImageVertex<N>; 6],
+}
+
+impl<N> ImageQuad<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    /// The image is upright as seen by the keyframe, on the image plane of its frustum.
+    pub fn new(iso: &Isometry3<N>, image_plane: &[Point3<N>; 4], tile: &Tile) -> Self {
+        let vertex = |corner: usize, u, v| ImageVertex {
+            position: iso * image_plane[corner],
+            uv: [u, v],
+        };
+
+        let top_left = vertex(0, tile.min[0], tile.min[1]);
+        let top_right = vertex(1, tile.max[0], tile.min[1]);
+        let bottom_right = vertex(2, tile.max[0], tile.max[1]);
+        let bottom_left = vertex(3, tile.min[0], tile.max[1]);
+
+        Self {
+            vertices: [
+                top_left,
+                bottom_left,
+                bottom_right,
+                top_left,
+                bottom_right,
+                top_right,
+            ],
+        }
+    }
+}
+
+impl<N> GpuVertex for ImageQuad<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    fn weight() -> u64 {
+        6
+    }
+}
+
+unsafe impl<N> bytemuck::Pod for ImageQuad<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+}
+unsafe impl<N> bytemuck::Zeroable for ImageQuad<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+}
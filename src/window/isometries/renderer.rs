@@ -1,11 +1,17 @@
 use core::marker::PhantomData;
 
+use super::super::camera::KeyFrameView;
+use super::atlas::ImageAtlas;
 use super::builder::IsometriesBuilder;
 use super::isometry::Isometry;
+use super::quad::ImageQuad;
 use super::source::IsometrySource;
-use crate::pipes::{GpuVec, PipelineBuilder, PipelineRenderer, VertexFormat};
+use crate::pipes::{
+    cast_isometry, Bounds, GpuVec, Layer, Pick, PickIndex, PickKind, PickRay, PipelineBuilder,
+    PipelineRenderer, VertexFormat,
+};
 
-use nalgebra::Point3;
+use nalgebra::{Isometry3, Point3};
 use slam_cv::Number;
 
 pub struct IsometriesRendener<N, S>
@@ -16,11 +22,50 @@ where
 {
     pub render_pipeline: wgpu::RenderPipeline,
     pub buffer: GpuVec<Isometry<N>>,
+    /// The images of the keyframes, if the source has any.
+    pub image_pipeline: wgpu::RenderPipeline,
+    pub images: GpuVec<ImageQuad<N>>,
+    pub atlas: ImageAtlas,
+    pub version: Option<u64>,
+    /// The name of the layer, the one of the source unless renamed.
+    pub name: String,
+    pub latest: Option<KeyFrameView>,
+    /// Built on the first pick after each update.
+    pub pick_index: Option<PickIndex>,
+    pub visible: bool,
+    /// Paints all the elements instead of their own colors, if set.
+    pub color: Option<[f32; 3]>,
+    /// Moves all the elements, if set.
+    pub transform: Option<Isometry3<N>>,
+    /// Multiplies the depth of the frustums.
+    pub scale: f32,
 
     pub number: PhantomData<N>,
     pub source: S,
 }
 
+impl<N, S> IsometriesRendener<N, S>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    S: 'static + IsometrySource<N>,
+{
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if !self.visible {
+            return;
+        }
+
+        if let Some(bind_group) = self.atlas.bind_group() {
+            render_pass.set_pipeline(&self.image_pipeline);
+            render_pass.set_bind_group(1, bind_group, &[]);
+            self.images.set_buffer(render_pass);
+        }
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        self.buffer.set_buffer(render_pass);
+    }
+}
+
 impl<N, S> PipelineBuilder<N> for IsometriesBuilder<N, S>
 where
     Self: Send,
@@ -44,20 +89,166 @@ where
     Point3<N>: VertexFormat<N>,
     S: IsometrySource<N>,
 {
-    fn render<'a>(&'a mut self, device: &wgpu::Device, render_pass: &mut wgpu::RenderPass<'a>) {
-        render_pass.set_pipeline(&self.render_pipeline);
+    fn bounds(&self) -> Option<Bounds> {
+        let isometries = self.buffer.as_slice().iter();
+        Bounds::from_points(isometries.flat_map(Isometry::positions), 0.0)
+    }
+
+    fn latest_keyframe(&self) -> Option<KeyFrameView> {
+        self.latest
+    }
 
-        let size = S::SIZE.into();
-        let color = S::COLOR.into();
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        vec![self as &mut dyn Layer]
+    }
 
-        let isometries = self
-            .source
-            .collect_visual_isometries()
-            .into_iter()
-            .map(|i| Isometry::from_iso(i, size, color))
-            .collect();
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        self.transform = Some(cast_isometry(transform));
+        // collect again with the new transform
+        self.version = None;
+    }
 
-        self.buffer.update(device, isometries);
-        self.buffer.set_buffer(render_pass);
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        // what is hidden is not under the cursor
+        if !self.visible {
+            return None;
+        }
+
+        let buffer = &self.buffer;
+        self.pick_index
+            .get_or_insert_with(|| {
+                let centers = buffer.as_slice().iter().map(Isometry::center);
+                PickIndex::new(PickKind::KeyFrame, centers)
+            })
+            .pick(ray)
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let version = self.source.version();
+        if version.is_none() || version != self.version {
+            let depth = S::DEPTH * N::from(self.scale).unwrap();
+            let color = self
+                .color
+                .unwrap_or_else(|| self.source.source_color())
+                .into();
+
+            let mut isometries = self.source.collect_visual_isometries();
+            if let Some(transform) = &self.transform {
+                for iso in isometries.iter_mut() {
+                    *iso = transform * *iso;
+                }
+            }
+
+            // the keyframes without their own intrinsics share those of the source
+            let shared = self.source.intrinsics();
+            let mut intrinsics = self.source.collect_intrinsics();
+            intrinsics.resize(isometries.len(), None);
+            let intrinsics: Vec<_> = intrinsics.into_iter().map(|i| i.or(shared)).collect();
+
+            self.latest = isometries
+                .last()
+                .map(|pose| KeyFrameView::new(pose, *intrinsics.last().unwrap()));
+
+            let image_planes: Vec<_> = intrinsics
+                .iter()
+                .map(|i| i.unwrap_or_default().image_plane(depth))
+                .collect();
+
+            let images = self.source.collect_images();
+            let tiles = self.atlas.update(device, queue, &images);
+            let quads = isometries
+                .iter()
+                .zip(&image_planes)
+                .zip(tiles)
+                .filter_map(|((iso, plane), tile)| Some(ImageQuad::new(iso, plane, &tile?)))
+                .collect();
+            self.images.update(quads);
+
+            let isometries = isometries
+                .into_iter()
+                .zip(&image_planes)
+                .map(|(i, plane)| Isometry::from_iso(i, plane, color))
+                .collect();
+
+            self.buffer.update(isometries);
+            self.version = version;
+            self.pick_index = None;
+        }
+
+        self.buffer.upload(device, queue);
+        self.images.upload(device, queue);
+    }
+
+    fn render<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        if S::DEPTH_TEST {
+            self.draw(render_pass);
+        }
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        if !S::DEPTH_TEST {
+            self.draw(render_pass);
+        }
+    }
+}
+
+impl<N, S> Layer for IsometriesRendener<N, S>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    S: IsometrySource<N>,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn count(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn color(&self) -> Option<[f32; 3]> {
+        self.color
+    }
+
+    fn set_color(&mut self, color: Option<[f32; 3]>) {
+        self.color = color;
+        // collect again with the new colors
+        self.version = None;
+    }
+
+    fn source_color(&self) -> [f32; 3] {
+        self.source.source_color()
+    }
+
+    fn frustum_scale(&self) -> Option<f32> {
+        Some(self.scale)
+    }
+
+    fn set_frustum_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        // collect again with the new depth
+        self.version = None;
     }
 }
@@ -1,5 +1,9 @@
+use std::sync::Arc;
+
+use super::super::camera::CameraIntrinsics;
 use crate::pipes::VertexFormat;
 
+use image::RgbaImage;
 use nalgebra::{Isometry3, Point3};
 use slam_cv::{Colors, Number};
 
@@ -8,8 +12,55 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
+    /// See `PointSource::NAME`.
+    const NAME: &'static str = "Keyframes";
     const COLOR: [f32; 3] = Colors::green();
-    const SIZE: [N; 2];
+    /// See `PointSource::DEPTH_TEST`.
+    const DEPTH_TEST: bool = true;
+    /// See `PointSource::STATIC`.
+    const STATIC: bool = false;
+    /// The distance from the optical center to the drawn image plane, in world units.
+    const DEPTH: N;
+
+    /// See `PointSource::name`.
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    /// See `PointSource::source_color`.
+    fn source_color(&self) -> [f32; 3] {
+        Self::COLOR
+    }
 
     fn collect_visual_isometries(&self) -> Vec<Isometry3<N>>;
+
+    /// The intrinsics of the cameras, to look through the latest keyframe.
+    ///
+    /// The frustums are drawn from them too, or from `CameraIntrinsics::default` if none.
+    fn intrinsics(&self) -> Option<CameraIntrinsics> {
+        None
+    }
+
+    /// The intrinsics of each keyframe, in the order of `collect_visual_isometries`,
+    /// if they differ from `intrinsics`.
+    fn collect_intrinsics(&self) -> Vec<Option<CameraIntrinsics>> {
+        vec![]
+    }
+
+    /// The images of the keyframes, in the order of `collect_visual_isometries`,
+    /// drawn downscaled inside their frustums.
+    ///
+    /// Only the images which are not the same `Arc` as in the last frame are uploaded again.
+    fn collect_images(&self) -> Vec<Option<Arc<RgbaImage>>> {
+        vec![]
+    }
+
+    /// See `PointSource::version`.
+    fn version(&self) -> Option<u64> {
+        if Self::STATIC {
+            Some(0)
+        } else {
+            None
+        }
+    }
 }
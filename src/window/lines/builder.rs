@@ -3,7 +3,7 @@ use core::marker::PhantomData;
 use super::line::Line;
 use super::renderer::LinesRendener;
 use super::source::LineSource;
-use crate::pipes::{GpuVec, StaticShaderModule, VertexFormat};
+use crate::pipes::{depth_stencil_state, GpuVec, StaticShaderModule, VertexFormat};
 
 use nalgebra::Point3;
 use slam_cv::Number;
@@ -39,12 +39,21 @@ where
         texture_format: wgpu::TextureFormat,
         uniform_bind_group_layout: &wgpu::BindGroupLayout,
     ) -> LinesRendener<N, S> {
-        let render_pipeline =
-            build_render_pipeline(device, texture_format, uniform_bind_group_layout);
+        let render_pipeline = build_render_pipeline(
+            device,
+            texture_format,
+            uniform_bind_group_layout,
+            S::DEPTH_TEST,
+        );
 
         LinesRendener {
             render_pipeline,
             buffer: GpuVec::new(wgpu::BufferUsage::VERTEX),
+            version: None,
+            name: self.source.name().to_string(),
+            visible: true,
+            color: None,
+            transform: None,
 
             number: Default::default(),
             source: self.source,
@@ -56,6 +65,7 @@ pub fn build_render_pipeline(
     device: &wgpu::Device,
     texture_format: wgpu::TextureFormat,
     uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    depth_test: bool,
 ) -> wgpu::RenderPipeline {
     const VS_SRC: StaticShaderModule = StaticShaderModule {
         spirv_source: include_bytes!("../points/shader.vert.spv"),
@@ -94,7 +104,7 @@ pub fn build_render_pipeline(
             write_mask: wgpu::ColorWrite::ALL,
         }],
         primitive_topology: wgpu::PrimitiveTopology::LineList,
-        depth_stencil_state: None,
+        depth_stencil_state: Some(depth_stencil_state(depth_test)),
         vertex_state: wgpu::VertexStateDescriptor {
             index_format: wgpu::IndexFormat::Uint16,
             vertex_buffers: &[Line::desc(&Line::attributes())],
@@ -4,9 +4,11 @@ use super::super::points::Point;
 use super::builder::LinesBuilder;
 use super::line::Line;
 use super::source::LineSource;
-use crate::pipes::{GpuVec, PipelineBuilder, PipelineRenderer, VertexFormat};
+use crate::pipes::{
+    cast_isometry, Bounds, GpuVec, Layer, PipelineBuilder, PipelineRenderer, VertexFormat,
+};
 
-use nalgebra::Point3;
+use nalgebra::{Isometry3, Point3};
 use slam_cv::Number;
 
 pub struct LinesRendener<N, S>
@@ -17,11 +19,33 @@ where
 {
     pub render_pipeline: wgpu::RenderPipeline,
     pub buffer: GpuVec<Line<N>>,
+    pub version: Option<u64>,
+    /// The name of the layer, the one of the source unless renamed.
+    pub name: String,
+    pub visible: bool,
+    /// Paints all the elements instead of their own colors, if set.
+    pub color: Option<[f32; 3]>,
+    /// Moves all the elements, if set.
+    pub transform: Option<Isometry3<N>>,
 
     pub number: PhantomData<N>,
     pub source: S,
 }
 
+impl<N, S> LinesRendener<N, S>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    S: 'static + LineSource<N>,
+{
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if self.visible {
+            render_pass.set_pipeline(&self.render_pipeline);
+            self.buffer.set_buffer(render_pass);
+        }
+    }
+}
+
 impl<N, S> PipelineBuilder<N> for LinesBuilder<N, S>
 where
     Self: Send,
@@ -45,28 +69,117 @@ where
     Point3<N>: VertexFormat<N>,
     S: LineSource<N>,
 {
-    fn render<'a>(&'a mut self, device: &wgpu::Device, render_pass: &mut wgpu::RenderPass<'a>) {
-        render_pass.set_pipeline(&self.render_pipeline);
-
-        let color = S::COLOR.into();
-
-        let lines = self
-            .source
-            .collect_visual_lines()
-            .into_iter()
-            .map(|[p1, p2]| Line {
-                start: Point {
-                    position: p1,
-                    color,
-                },
-                end: Point {
-                    position: p2,
-                    color,
-                },
-            })
-            .collect();
-
-        self.buffer.update(device, lines);
-        self.buffer.set_buffer(render_pass);
+    fn bounds(&self) -> Option<Bounds> {
+        let points = self.buffer.as_slice().iter();
+        Bounds::from_points(
+            points.flat_map(|l| vec![l.start.position, l.end.position]),
+            0.0,
+        )
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        vec![self as &mut dyn Layer]
+    }
+
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        self.transform = Some(cast_isometry(transform));
+        // collect again with the new transform
+        self.version = None;
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let version = self.source.version();
+        if version.is_none() || version != self.version {
+            let lines = self
+                .source
+                .collect_visual_colored_lines()
+                .into_iter()
+                .map(|([p1, p2], color)| {
+                    let (p1, p2) = match &self.transform {
+                        Some(transform) => (transform * p1, transform * p2),
+                        None => (p1, p2),
+                    };
+                    let color = self.color.unwrap_or(color).into();
+                    Line {
+                        start: Point {
+                            position: p1,
+                            color,
+                        },
+                        end: Point {
+                            position: p2,
+                            color,
+                        },
+                    }
+                })
+                .collect();
+
+            self.buffer.update(lines);
+            self.version = version;
+        }
+
+        self.buffer.upload(device, queue);
+    }
+
+    fn render<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        if S::DEPTH_TEST {
+            self.draw(render_pass);
+        }
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        if !S::DEPTH_TEST {
+            self.draw(render_pass);
+        }
+    }
+}
+
+impl<N, S> Layer for LinesRendener<N, S>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    S: LineSource<N>,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn count(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn color(&self) -> Option<[f32; 3]> {
+        self.color
+    }
+
+    fn set_color(&mut self, color: Option<[f32; 3]>) {
+        self.color = color;
+        // collect again with the new colors
+        self.version = None;
+    }
+
+    fn source_color(&self) -> [f32; 3] {
+        self.source.source_color()
     }
 }
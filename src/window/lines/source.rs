@@ -8,7 +8,41 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
+    /// See `PointSource::NAME`.
+    const NAME: &'static str = "Lines";
     const COLOR: [f32; 3] = Colors::blue();
+    /// See `PointSource::DEPTH_TEST`.
+    const DEPTH_TEST: bool = true;
+    /// See `PointSource::STATIC`.
+    const STATIC: bool = false;
+
+    /// See `PointSource::name`.
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    /// See `PointSource::source_color`.
+    fn source_color(&self) -> [f32; 3] {
+        Self::COLOR
+    }
 
     fn collect_visual_lines(&self) -> Vec<[Point3<N>; 2]>;
+
+    /// See `PointSource::collect_visual_colored_points`.
+    fn collect_visual_colored_lines(&self) -> Vec<([Point3<N>; 2], [f32; 3])> {
+        let color = self.source_color();
+        self.collect_visual_lines()
+            .into_iter()
+            .map(|l| (l, color))
+            .collect()
+    }
+
+    /// See `PointSource::version`.
+    fn version(&self) -> Option<u64> {
+        if Self::STATIC {
+            Some(0)
+        } else {
+            None
+        }
+    }
 }
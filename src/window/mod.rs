@@ -1,8 +1,17 @@
 mod base;
 mod builder;
 mod camera;
+mod capture;
+mod colormap;
+mod context;
 mod event;
+mod gizmo;
+mod headless;
+mod highlight;
+mod input;
+mod overlay;
 mod uniform;
+mod viewport;
 
 // Shaders, Pipelines
 mod lines;
@@ -14,11 +23,19 @@ mod isometries;
 pub mod models;
 
 pub use self::base::Window;
-pub use self::builder::WindowBuilder;
-pub use self::camera::{CameraControllerConfig, CameraFrustum};
+pub use self::builder::{ErrorCallback, WindowBuilder};
+pub use self::camera::{
+    CameraControllerConfig, CameraFrustum, CameraIntrinsics, CameraMode, CameraTracking,
+    Convention, KeyFrameView, Projection, ViewPreset,
+};
+pub use self::capture::WindowCommand;
+pub use self::colormap::Colormap;
 pub use self::event::WindowEventState;
+pub use self::headless::HeadlessWindow;
+pub use self::input::{Action, Input, InputCallback, InputMap, PickCallback};
 
 pub use self::lines::LineSource;
-pub use self::points::PointSource;
+pub use self::points::{PointShape, PointSource, PointStyle};
+pub use self::viewport::{ViewLayout, ViewportCamera};
 
 pub use self::isometries::IsometrySource;
@@ -0,0 +1,237 @@
+use super::super::builder::WindowBuilder;
+use super::super::camera::KeyFrameView;
+use super::super::colormap::Colormap;
+use super::super::isometries::{IsometriesBuilder, IsometriesRendener, IsometrySource};
+use super::super::lines::{LineSource, LinesBuilder, LinesRendener};
+use crate::eval::Comparison;
+use crate::pipes::{
+    Bounds, Layer, Pick, PickRay, PipelineBuilder, PipelineDataBuilder, PipelineRenderer,
+};
+
+use nalgebra::{Isometry3, Point3};
+use slam_cv::{Colors, Number};
+
+/// The ground truth (green) and the aligned estimate (blue),
+/// with the error of each pair colored by its length, each in its own layer.
+#[derive(Clone)]
+pub struct ComparisonModel {
+    ground_truth: Vec<Isometry3<f32>>,
+    estimate: Vec<Isometry3<f32>>,
+    errors: Vec<f32>,
+}
+
+impl ComparisonModel {
+    pub fn new(comparison: &Comparison) -> Self {
+        let convert =
+            |poses: &[Isometry3<f64>]| poses.iter().map(|pose| nalgebra::convert(*pose)).collect();
+
+        Self {
+            ground_truth: convert(&comparison.ground_truth),
+            estimate: convert(&comparison.estimate),
+            errors: comparison.errors.iter().map(|&e| e as f32).collect(),
+        }
+    }
+
+    /// The paths, then the errors.
+    fn lines(&self) -> Vec<ComparisonLines> {
+        let max_error = self.errors.iter().cloned().fold(0.0, f32::max);
+
+        let errors = self
+            .ground_truth
+            .iter()
+            .zip(&self.estimate)
+            .zip(&self.errors)
+            .map(|((gt, est), &error)| {
+                (
+                    [gt.translation.vector.into(), est.translation.vector.into()],
+                    Colormap::Turbo.map_range(error, 0.0, max_error),
+                )
+            })
+            .collect();
+
+        vec![
+            ComparisonLines::path("Ground truth", Colors::green(), &self.ground_truth),
+            ComparisonLines::path("Estimate", Colors::blue(), &self.estimate),
+            ComparisonLines {
+                name: "Errors",
+                // the largest error
+                color: Colormap::Turbo.map(1.0),
+                lines: errors,
+            },
+        ]
+    }
+}
+
+impl PipelineDataBuilder<f32> for ComparisonModel {
+    type Builder = Self;
+
+    fn default_window(&self) -> WindowBuilder<f32> {
+        WindowBuilder {
+            title: Some("Trajectory Comparison Viewer".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn build_data(self) -> Self::Builder {
+        self
+    }
+}
+
+impl<N> PipelineBuilder<N> for ComparisonModel
+where
+    N: 'static + Number,
+{
+    fn build(
+        self: Box<Self>,
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Box<dyn PipelineRenderer> {
+        let comparison = *self;
+        Box::new(ComparisonRenderer {
+            lines: comparison
+                .lines()
+                .into_iter()
+                .map(|lines| {
+                    LinesBuilder::new(lines).build(
+                        device,
+                        texture_format,
+                        uniform_bind_group_layout,
+                    )
+                })
+                .collect(),
+            isometries: IsometriesBuilder::new(comparison).build(
+                device,
+                texture_format,
+                uniform_bind_group_layout,
+            ),
+        })
+    }
+}
+
+pub struct ComparisonRenderer {
+    lines: Vec<LinesRendener<f32, ComparisonLines>>,
+    isometries: IsometriesRendener<f32, ComparisonModel>,
+}
+
+impl PipelineRenderer for ComparisonRenderer {
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::union_all(
+            self.lines
+                .iter()
+                .map(|l| l.bounds())
+                .chain(Some(self.isometries.bounds())),
+        )
+    }
+
+    fn latest_keyframe(&self) -> Option<KeyFrameView> {
+        self.isometries.latest_keyframe()
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        self.lines
+            .iter_mut()
+            .flat_map(|l| l.layers())
+            .chain(self.isometries.layers())
+            .collect()
+    }
+
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        for lines in &mut self.lines {
+            lines.set_transform(transform);
+        }
+        self.isometries.set_transform(transform);
+    }
+
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        self.isometries.pick(ray)
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        for lines in &mut self.lines {
+            lines.prepare(device, queue);
+        }
+        self.isometries.prepare(device, queue);
+    }
+
+    fn render<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        for lines in &self.lines {
+            lines.render(device, queue, render_pass);
+        }
+        self.isometries.render(device, queue, render_pass);
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        for lines in &self.lines {
+            lines.render_overlay(device, queue, render_pass);
+        }
+        self.isometries.render_overlay(device, queue, render_pass);
+    }
+}
+
+fn path(poses: &[Isometry3<f32>]) -> impl Iterator<Item = [Point3<f32>; 2]> + '_ {
+    poses.windows(2).map(|p| {
+        [
+            p[0].translation.vector.into(),
+            p[1].translation.vector.into(),
+        ]
+    })
+}
+
+/// The colored lines of a layer of a comparison.
+#[derive(Clone)]
+pub struct ComparisonLines {
+    name: &'static str,
+    color: [f32; 3],
+    lines: Vec<([Point3<f32>; 2], [f32; 3])>,
+}
+
+impl ComparisonLines {
+    fn path(name: &'static str, color: [f32; 3], poses: &[Isometry3<f32>]) -> Self {
+        Self {
+            name,
+            color,
+            lines: path(poses).map(|line| (line, color)).collect(),
+        }
+    }
+}
+
+impl LineSource<f32> for ComparisonLines {
+    const STATIC: bool = true;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn source_color(&self) -> [f32; 3] {
+        self.color
+    }
+
+    fn collect_visual_lines(&self) -> Vec<[Point3<f32>; 2]> {
+        self.lines.iter().map(|&(line, _)| line).collect()
+    }
+
+    fn collect_visual_colored_lines(&self) -> Vec<([Point3<f32>; 2], [f32; 3])> {
+        self.lines.clone()
+    }
+}
+
+impl IsometrySource<f32> for ComparisonModel {
+    const COLOR: [f32; 3] = Colors::blue();
+    const DEPTH: f32 = 0.3;
+    const STATIC: bool = true;
+
+    fn collect_visual_isometries(&self) -> Vec<Isometry3<f32>> {
+        self.estimate.clone()
+    }
+}
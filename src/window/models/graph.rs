@@ -0,0 +1,235 @@
+use super::super::colormap::Colormap;
+
+use nalgebra::Point3;
+use slam_cv::{Colors, Number};
+
+/// How weak the faintest covisibility edges are drawn, over the background.
+const MIN_OPACITY: f32 = 0.2;
+
+/// The kind of an edge between two keyframes, as in ORB-SLAM.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// The keyframes observe common landmarks, weighted by their number.
+    Covisibility,
+    /// A keyframe and its parent in the spanning tree of the essential graph.
+    SpanningTree,
+    /// A loop closed between the keyframes.
+    LoopClosure,
+}
+
+impl EdgeKind {
+    pub const ALL: [Self; 3] = [Self::Covisibility, Self::SpanningTree, Self::LoopClosure];
+
+    /// The name of the layer drawing the edges of the kind.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Covisibility => "Covisibility",
+            Self::SpanningTree => "Spanning tree",
+            Self::LoopClosure => "Loop closures",
+        }
+    }
+}
+
+/// An edge between two keyframes, by their indices in the world.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KeyFrameEdge {
+    pub from: usize,
+    pub to: usize,
+    pub kind: EdgeKind,
+    /// e.g. the number of landmarks both keyframes observe.
+    pub weight: f32,
+}
+
+/// The edges between the keyframes of a world, drawn as a layer per kind apart from the
+/// trajectory.
+#[derive(Clone, Debug)]
+pub struct KeyFrameGraph {
+    pub edges: Vec<KeyFrameEdge>,
+    /// The weaker covisibility edges are hidden, e.g. below `100.0` as in the essential graph.
+    pub min_weight: f32,
+    /// Maps the weights of the covisibility edges, which fade as they are weaker.
+    pub colormap: Colormap,
+}
+
+impl KeyFrameGraph {
+    pub fn new(edges: Vec<KeyFrameEdge>) -> Self {
+        Self {
+            edges,
+            min_weight: 0.0,
+            colormap: Colormap::Viridis,
+        }
+    }
+
+    pub fn with_min_weight(mut self, min_weight: f32) -> Self {
+        self.min_weight = min_weight;
+        self
+    }
+
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    pub fn has(&self, kind: EdgeKind) -> bool {
+        self.edges.iter().any(|e| e.kind == kind)
+    }
+
+    /// The color of the edges of the kind, in the legend.
+    pub fn color(&self, kind: EdgeKind) -> [f32; 3] {
+        match kind {
+            EdgeKind::Covisibility => self.colormap.map(1.0),
+            EdgeKind::SpanningTree => [1.0, 0.6, 0.0],
+            EdgeKind::LoopClosure => Colors::red(),
+        }
+    }
+
+    /// The edges of the kind between the keyframes at the positions, with their colors.
+    ///
+    /// The edges to missing keyframes are skipped.
+    pub fn collect_lines<N>(
+        &self,
+        kind: EdgeKind,
+        positions: &[Point3<N>],
+    ) -> Vec<([Point3<N>; 2], [f32; 3])>
+    where
+        N: Number,
+    {
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .filter(|e| e.kind == kind)
+            .filter(|e| kind != EdgeKind::Covisibility || e.weight >= self.min_weight)
+            .filter(|e| e.from < positions.len() && e.to < positions.len())
+            .collect();
+
+        let max_weight = edges
+            .iter()
+            .map(|e| e.weight)
+            .fold(self.min_weight, f32::max);
+
+        edges
+            .into_iter()
+            .map(|e| {
+                let line = [positions[e.from], positions[e.to]];
+                let color = match kind {
+                    EdgeKind::Covisibility => {
+                        let t = (e.weight - self.min_weight) / (max_weight - self.min_weight);
+                        let t = if t.is_finite() { t } else { 1.0 };
+
+                        // faded into the black background, as the lines are opaque
+                        let opacity = MIN_OPACITY + (1.0 - MIN_OPACITY) * t;
+                        let color = self.colormap.map(t);
+                        [color[0] * opacity, color[1] * opacity, color[2] * opacity]
+                    }
+                    _ => self.color(kind),
+                };
+                (line, color)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(from: usize, to: usize, kind: EdgeKind, weight: f32) -> KeyFrameEdge {
+        KeyFrameEdge {
+            from,
+            to,
+            kind,
+            weight,
+        }
+    }
+
+    fn positions() -> Vec<Point3<f32>> {
+        (0..3).map(|i| Point3::new(i as f32, 0.0, 0.0)).collect()
+    }
+
+    fn faded(color: [f32; 3], opacity: f32) -> [f32; 3] {
+        [color[0] * opacity, color[1] * opacity, color[2] * opacity]
+    }
+
+    #[test]
+    fn min_weight() {
+        let graph = KeyFrameGraph::new(vec![
+            edge(0, 1, EdgeKind::Covisibility, 50.0),
+            edge(1, 2, EdgeKind::Covisibility, 150.0),
+            edge(0, 2, EdgeKind::LoopClosure, 0.0),
+        ])
+        .with_min_weight(100.0);
+
+        let lines = graph.collect_lines(EdgeKind::Covisibility, &positions());
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].0, [positions()[1], positions()[2]]);
+
+        // only the covisibility edges are weighted
+        let lines = graph.collect_lines(EdgeKind::LoopClosure, &positions());
+        assert_eq!(
+            lines,
+            vec![([positions()[0], positions()[2]], Colors::red())]
+        );
+
+        assert!(graph
+            .collect_lines(EdgeKind::SpanningTree, &positions())
+            .is_empty());
+    }
+
+    #[test]
+    fn missing_keyframes() {
+        let graph = KeyFrameGraph::new(vec![
+            edge(0, 3, EdgeKind::SpanningTree, 1.0),
+            edge(3, 0, EdgeKind::SpanningTree, 1.0),
+            edge(2, 1, EdgeKind::SpanningTree, 1.0),
+        ]);
+
+        let lines = graph.collect_lines(EdgeKind::SpanningTree, &positions());
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].0, [positions()[2], positions()[1]]);
+
+        assert!(graph
+            .collect_lines(EdgeKind::SpanningTree, &positions()[..0])
+            .is_empty());
+    }
+
+    #[test]
+    fn weights_fade() {
+        let graph = KeyFrameGraph::new(vec![
+            edge(0, 1, EdgeKind::Covisibility, 10.0),
+            edge(1, 2, EdgeKind::Covisibility, 20.0),
+            edge(0, 2, EdgeKind::Covisibility, 30.0),
+        ])
+        .with_min_weight(10.0)
+        .with_colormap(Colormap::Jet);
+
+        let colors: Vec<_> = graph
+            .collect_lines(EdgeKind::Covisibility, &positions())
+            .into_iter()
+            .map(|(_, color)| color)
+            .collect();
+
+        assert_eq!(
+            colors,
+            vec![
+                faded(Colormap::Jet.map(0.0), MIN_OPACITY),
+                faded(Colormap::Jet.map(0.5), (1.0 + MIN_OPACITY) / 2.0),
+                Colormap::Jet.map(1.0),
+            ]
+        );
+        assert_eq!(graph.color(EdgeKind::Covisibility), Colormap::Jet.map(1.0));
+    }
+
+    #[test]
+    fn same_weights() {
+        // every edge at the minimum weight is drawn fully, not as NaN
+        let graph = KeyFrameGraph::new(vec![
+            edge(0, 1, EdgeKind::Covisibility, 5.0),
+            edge(1, 2, EdgeKind::Covisibility, 5.0),
+        ])
+        .with_min_weight(5.0);
+
+        for (_, color) in graph.collect_lines(EdgeKind::Covisibility, &positions()) {
+            assert_eq!(color, Colormap::Viridis.map(1.0));
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use core::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{mpsc, Arc};
+
+use super::super::builder::WindowBuilder;
+use super::super::camera::{CameraIntrinsics, KeyFrameView};
+use super::super::isometries::{IsometriesBuilder, IsometriesRendener, IsometrySource};
+use super::super::lines::{LineSource, LinesBuilder, LinesRendener};
+use super::super::points::{PointSource, PointStyle, PointsBuilder, PointsRendener};
+use crate::pipes::{
+    Bounds, Layer, Pick, PickRay, PipelineBuilder, PipelineDataBuilder, PipelineRenderer,
+    VertexFormat,
+};
+
+use image::RgbaImage;
+use nalgebra::{Isometry3, Point3};
+use slam_cv::Number;
+
+/// An update pushed from another thread into a running viewer.
+///
+/// All the messages received between two frames are applied at once,
+/// so sending `Clear` followed by the new data replaces the map without flickering.
+#[derive(Clone, Debug)]
+pub enum ViewerMessage<N>
+where
+    N: 'static + Number,
+{
+    /// Appends new landmarks to the map.
+    Landmarks(Vec<Point3<N>>),
+    /// Appends new keyframes to the trajectory.
+    KeyFrames(Vec<Isometry3<N>>),
+    /// Sets the image of a keyframe by its index, drawn inside its frustum.
+    KeyFrameImage(usize, RgbaImage),
+    /// Replaces the current matches.
+    Matches(Vec<[Point3<N>; 2]>),
+    /// Sets the intrinsics of the keyframes, to look through the latest one.
+    Intrinsics(CameraIntrinsics),
+    /// Removes all the received data, but the intrinsics.
+    Clear,
+}
+
+#[derive(Debug)]
+struct LiveData<N>
+where
+    N: 'static + Number,
+{
+    landmarks: Vec<Point3<N>>,
+    keyframes: Vec<Isometry3<N>>,
+    images: Vec<Option<Arc<RgbaImage>>>,
+    matches: Vec<[Point3<N>; 2]>,
+    intrinsics: Option<CameraIntrinsics>,
+
+    version: u64,
+}
+
+impl<N> Default for LiveData<N>
+where
+    N: 'static + Number,
+{
+    fn default() -> Self {
+        Self {
+            landmarks: vec![],
+            keyframes: vec![],
+            images: vec![],
+            matches: vec![],
+            intrinsics: None,
+
+            version: 0,
+        }
+    }
+}
+
+impl<N> LiveData<N>
+where
+    N: 'static + Number,
+{
+    fn apply(&mut self, message: ViewerMessage<N>) {
+        match message {
+            ViewerMessage::Landmarks(landmarks) => self.landmarks.extend(landmarks),
+            ViewerMessage::KeyFrames(keyframes) => self.keyframes.extend(keyframes),
+            ViewerMessage::KeyFrameImage(index, image) => {
+                if self.images.len() <= index {
+                    self.images.resize(index + 1, None);
+                }
+                self.images[index] = Some(Arc::new(image));
+            }
+            ViewerMessage::Matches(matches) => self.matches = matches,
+            ViewerMessage::Intrinsics(intrinsics) => self.intrinsics = Some(intrinsics),
+            ViewerMessage::Clear => {
+                self.landmarks.clear();
+                self.keyframes.clear();
+                self.images.clear();
+                self.matches.clear();
+            }
+        }
+        self.version += 1;
+    }
+}
+
+pub struct LiveModel<N>
+where
+    N: 'static + Number,
+{
+    receiver: mpsc::Receiver<ViewerMessage<N>>,
+}
+
+impl<N> LiveModel<N>
+where
+    N: 'static + Number,
+{
+    pub fn new(receiver: mpsc::Receiver<ViewerMessage<N>>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl PipelineDataBuilder<f32> for LiveModel<f32> {
+    type Builder = Self;
+
+    fn default_window(&self) -> WindowBuilder<f32> {
+        WindowBuilder {
+            title: Some("Live Map Viewer".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn build_data(self) -> Self::Builder {
+        self
+    }
+}
+
+impl<N> PipelineBuilder<N> for LiveModel<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    LiveSource<N>: PointSource<N> + LineSource<N> + IsometrySource<N>,
+{
+    fn build(
+        self: Box<Self>,
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Box<dyn PipelineRenderer> {
+        let source = LiveSource {
+            data: Default::default(),
+        };
+
+        Box::new(LiveRenderer {
+            receiver: self.receiver,
+            data: source.data.clone(),
+
+            points: PointsBuilder::new(source.clone()).build(
+                device,
+                texture_format,
+                uniform_bind_group_layout,
+            ),
+            lines: LinesBuilder::new(source.clone()).build(
+                device,
+                texture_format,
+                uniform_bind_group_layout,
+            ),
+            isometries: IsometriesBuilder::new(source).build(
+                device,
+                texture_format,
+                uniform_bind_group_layout,
+            ),
+        })
+    }
+}
+
+/// A view of the received data, shared by the sub-renderers on the render thread.
+#[derive(Clone)]
+pub struct LiveSource<N>
+where
+    N: 'static + Number,
+{
+    data: Rc<RefCell<LiveData<N>>>,
+}
+
+pub struct LiveRenderer<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    LiveSource<N>: PointSource<N> + LineSource<N> + IsometrySource<N>,
+{
+    receiver: mpsc::Receiver<ViewerMessage<N>>,
+    data: Rc<RefCell<LiveData<N>>>,
+
+    points: PointsRendener<N, LiveSource<N>>,
+    lines: LinesRendener<N, LiveSource<N>>,
+    isometries: IsometriesRendener<N, LiveSource<N>>,
+}
+
+impl<N> PipelineRenderer for LiveRenderer<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    LiveSource<N>: PointSource<N> + LineSource<N> + IsometrySource<N>,
+{
+    fn sync(&mut self) {
+        let mut data = self.data.borrow_mut();
+        for message in self.receiver.try_iter() {
+            data.apply(message);
+        }
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::union_all(vec![
+            self.points.bounds(),
+            self.lines.bounds(),
+            self.isometries.bounds(),
+        ])
+    }
+
+    fn latest_keyframe(&self) -> Option<KeyFrameView> {
+        self.isometries.latest_keyframe()
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        self.points
+            .layers()
+            .into_iter()
+            .chain(self.lines.layers())
+            .chain(self.isometries.layers())
+            .collect()
+    }
+
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        self.points.set_transform(transform);
+        self.lines.set_transform(transform);
+        self.isometries.set_transform(transform);
+    }
+
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        Pick::nearest(vec![self.points.pick(ray), self.isometries.pick(ray)])
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.points.prepare(device, queue);
+        self.lines.prepare(device, queue);
+        self.isometries.prepare(device, queue);
+    }
+
+    fn render<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.points.render(device, queue, render_pass);
+        self.lines.render(device, queue, render_pass);
+        self.isometries.render(device, queue, render_pass);
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.points.render_overlay(device, queue, render_pass);
+        self.lines.render_overlay(device, queue, render_pass);
+        self.isometries.render_overlay(device, queue, render_pass);
+    }
+}
+
+impl<N> PointSource<N> for LiveSource<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    const NAME: &'static str = "Landmarks";
+    const STYLE: PointStyle = PointStyle::LANDMARKS;
+
+    fn collect_visual_points(&self) -> Vec<Point3<N>> {
+        self.data.borrow().landmarks.clone()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.data.borrow().version)
+    }
+}
+
+impl<N> LineSource<N> for LiveSource<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    const NAME: &'static str = "Trajectory & matches";
+
+    fn collect_visual_lines(&self) -> Vec<[Point3<N>; 2]> {
+        let data = self.data.borrow();
+
+        let trajectory = data.keyframes.windows(2).map(|kf| {
+            [
+                kf[0].translation.vector.into(),
+                kf[1].translation.vector.into(),
+            ]
+        });
+
+        trajectory.chain(data.matches.iter().cloned()).collect()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.data.borrow().version)
+    }
+}
+
+impl IsometrySource<f32> for LiveSource<f32> {
+    const DEPTH: f32 = 0.3;
+
+    fn collect_visual_isometries(&self) -> Vec<Isometry3<f32>> {
+        self.data.borrow().keyframes.clone()
+    }
+
+    fn collect_images(&self) -> Vec<Option<Arc<RgbaImage>>> {
+        self.data.borrow().images.clone()
+    }
+
+    fn intrinsics(&self) -> Option<CameraIntrinsics> {
+        self.data.borrow().intrinsics
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.data.borrow().version)
+    }
+}
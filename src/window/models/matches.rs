@@ -1,11 +1,15 @@
 use super::super::builder::WindowBuilder;
-use super::super::camera::{CameraControllerConfig, CameraFrustum};
 use super::super::lines::{LineSource, LinesBuilder, LinesRendener};
 use super::super::points::{PointSource, PointsBuilder, PointsRendener};
-use crate::pipes::{PipelineBuilder, PipelineDataBuilder, PipelineRenderer, VertexFormat};
+use crate::pipes::{
+    Bounds, Layer, Pick, PickRay, PipelineBuilder, PipelineDataBuilder, PipelineRenderer,
+    VertexFormat,
+};
 
 use cv_core::FeatureMatch;
-use nalgebra::{base::allocator::Allocator, DefaultAllocator, DimName, Point, Point3, U2, U3};
+use nalgebra::{
+    base::allocator::Allocator, DefaultAllocator, DimName, Isometry3, Point, Point3, U2, U3,
+};
 use slam_cv::Number;
 
 #[derive(Clone)]
@@ -40,17 +44,7 @@ where
     fn default_window(&self) -> WindowBuilder<f32> {
         WindowBuilder {
             title: Some("Matches Viewer".to_string()),
-            framerate: Some(120),
-
-            camera: CameraFrustum {
-                eye: Point3::new(0., 2., 5.),
-                at: Point3::new(0., 0., 0.),
-
-                fovy: std::f32::consts::FRAC_PI_4,
-                znear: 0.1,
-                zfar: 100.0,
-            },
-            camera_controller: CameraControllerConfig::default(),
+            ..Default::default()
         }
     }
 
@@ -115,10 +109,59 @@ where
     DefaultAllocator: Allocator<N, D>,
     MatchesModel<N, D>: PointSource<N> + LineSource<N>,
 {
-    fn render<'a>(&'a mut self, device: &wgpu::Device, render_pass: &mut wgpu::RenderPass<'a>) {
-        self.points_1.render(device, render_pass);
-        self.points_2.render(device, render_pass);
-        self.lines.render(device, render_pass);
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::union_all(vec![
+            self.points_1.bounds(),
+            self.points_2.bounds(),
+            self.lines.bounds(),
+        ])
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        self.points_1
+            .layers()
+            .into_iter()
+            .chain(self.points_2.layers())
+            .chain(self.lines.layers())
+            .collect()
+    }
+
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        self.points_1.set_transform(transform);
+        self.points_2.set_transform(transform);
+        self.lines.set_transform(transform);
+    }
+
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        Pick::nearest(vec![self.points_1.pick(ray), self.points_2.pick(ray)])
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.points_1.prepare(device, queue);
+        self.points_2.prepare(device, queue);
+        self.lines.prepare(device, queue);
+    }
+
+    fn render<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.points_1.render(device, queue, render_pass);
+        self.points_2.render(device, queue, render_pass);
+        self.lines.render(device, queue, render_pass);
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.points_1.render_overlay(device, queue, render_pass);
+        self.points_2.render_overlay(device, queue, render_pass);
+        self.lines.render_overlay(device, queue, render_pass);
     }
 }
 
@@ -127,6 +170,9 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
+    const NAME: &'static str = "Keypoints";
+    const STATIC: bool = true;
+
     fn collect_visual_points(&self) -> Vec<Point3<N>> {
         self.matches
             .iter()
@@ -145,6 +191,9 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
+    const NAME: &'static str = "Keypoints";
+    const STATIC: bool = true;
+
     fn collect_visual_points(&self) -> Vec<Point3<N>> {
         self.matches
             .iter()
@@ -159,6 +208,9 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
+    const NAME: &'static str = "Matches";
+    const STATIC: bool = true;
+
     fn collect_visual_lines(&self) -> Vec<[Point3<N>; 2]> {
         self.matches
             .iter()
@@ -176,6 +228,9 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
+    const NAME: &'static str = "Matches";
+    const STATIC: bool = true;
+
     fn collect_visual_lines(&self) -> Vec<[Point3<N>; 2]> {
         self.matches.iter().map(|&m| [m.0, m.1]).collect()
     }
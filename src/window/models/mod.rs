@@ -1,10 +1,20 @@
+mod comparison;
+mod graph;
+mod live;
 mod points;
+mod scene;
+mod trajectory;
 mod world;
 
 #[cfg(feature = "rust-cv")]
 mod matches;
 
+pub use self::comparison::ComparisonModel;
+pub use self::graph::{EdgeKind, KeyFrameEdge, KeyFrameGraph};
+pub use self::live::{LiveModel, ViewerMessage};
 pub use self::points::PointsModel;
+pub use self::scene::SceneModel;
+pub use self::trajectory::TrajectoryModel;
 pub use self::world::WorldModel;
 
 #[cfg(feature = "rust-cv")]
@@ -1,5 +1,5 @@
 use super::super::builder::WindowBuilder;
-use super::super::camera::{CameraControllerConfig, CameraFrustum};
+use super::super::camera::{CameraFrustum, Projection};
 use super::super::points::{PointSource, PointsBuilder};
 use crate::pipes::{PipelineBuilder, PipelineDataBuilder, PipelineRenderer};
 
@@ -13,6 +13,7 @@ where
     DefaultAllocator: Allocator<N, D>,
 {
     points: Vec<Point<N, D>>,
+    colors: Option<Vec<[f32; 3]>>,
 }
 
 impl<N, D> PointsModel<N, D>
@@ -22,7 +23,29 @@ where
     DefaultAllocator: Allocator<N, D>,
 {
     pub fn new(points: Vec<Point<N, D>>) -> Self {
-        Self { points }
+        Self {
+            points,
+            colors: None,
+        }
+    }
+
+    /// Paints each point with its own color.
+    ///
+    /// Panics if `colors` has not the same length as `points`.
+    pub fn with_colors(points: Vec<Point<N, D>>, colors: Vec<[f32; 3]>) -> Self {
+        assert_eq!(points.len(), colors.len(), "each point should have a color");
+
+        Self {
+            points,
+            colors: Some(colors),
+        }
+    }
+
+    fn colorize(&self, points: Vec<Point3<f32>>, color: [f32; 3]) -> Vec<(Point3<f32>, [f32; 3])> {
+        match &self.colors {
+            Some(colors) => points.into_iter().zip(colors.iter().cloned()).collect(),
+            None => points.into_iter().map(|p| (p, color)).collect(),
+        }
     }
 }
 
@@ -35,19 +58,26 @@ where
     type Builder = Self;
 
     fn default_window(&self) -> WindowBuilder<f32> {
+        // the 2d points lie on the plane z=0, so they are seen from the front
+        let is_2d = D::dim() == 2;
+
         WindowBuilder {
             title: Some("2d Points Viewer".to_string()),
-            framerate: Some(120),
 
             camera: CameraFrustum {
-                eye: Point3::new(0., 2., 5.),
-                at: Point3::new(0., 0., 0.),
-
-                fovy: std::f32::consts::FRAC_PI_4,
-                znear: 0.1,
-                zfar: 100.0,
+                eye: if is_2d {
+                    Point3::new(0., 0., 5.)
+                } else {
+                    Point3::new(0., 2., 5.)
+                },
+                projection: if is_2d {
+                    Projection::Orthographic
+                } else {
+                    Projection::Perspective
+                },
+                ..Default::default()
             },
-            camera_controller: CameraControllerConfig::default(),
+            ..Default::default()
         }
     }
 
@@ -79,34 +109,58 @@ where
 }
 
 impl PointSource<f32> for PointsModel<f32, U2> {
+    const STATIC: bool = true;
+
     fn collect_visual_points(&self) -> Vec<Point3<f32>> {
         self.points
             .iter()
             .map(|p| Point3::new(p.x, p.y, 0.0))
             .collect()
     }
+
+    fn collect_visual_colored_points(&self) -> Vec<(Point3<f32>, [f32; 3])> {
+        self.colorize(self.collect_visual_points(), Self::COLOR)
+    }
 }
 
 impl PointSource<f32> for PointsModel<f64, U2> {
+    const STATIC: bool = true;
+
     fn collect_visual_points(&self) -> Vec<Point3<f32>> {
         self.points
             .iter()
             .map(|p| Point3::new(p.x as f32, p.y as f32, 0.0))
             .collect()
     }
+
+    fn collect_visual_colored_points(&self) -> Vec<(Point3<f32>, [f32; 3])> {
+        self.colorize(self.collect_visual_points(), Self::COLOR)
+    }
 }
 
 impl PointSource<f32> for PointsModel<f32, U3> {
+    const STATIC: bool = true;
+
     fn collect_visual_points(&self) -> Vec<Point3<f32>> {
         self.points.clone()
     }
+
+    fn collect_visual_colored_points(&self) -> Vec<(Point3<f32>, [f32; 3])> {
+        self.colorize(self.collect_visual_points(), Self::COLOR)
+    }
 }
 
 impl PointSource<f32> for PointsModel<f64, U3> {
+    const STATIC: bool = true;
+
     fn collect_visual_points(&self) -> Vec<Point3<f32>> {
         self.points
             .iter()
             .map(|p| Point3::new(p.x as f32, p.y as f32, p.z as f32))
             .collect()
     }
+
+    fn collect_visual_colored_points(&self) -> Vec<(Point3<f32>, [f32; 3])> {
+        self.colorize(self.collect_visual_points(), Self::COLOR)
+    }
 }
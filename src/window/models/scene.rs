@@ -0,0 +1,181 @@
+use super::super::camera::KeyFrameView;
+use crate::pipes::{Bounds, Layer, Pick, PickRay, PipelineBuilder, PipelineRenderer};
+
+use nalgebra::Isometry3;
+use slam_cv::Number;
+
+/// Several models drawn together in one window, each moved by its own transform.
+pub struct SceneModel<N>
+where
+    N: 'static + Number,
+{
+    models: Vec<ScenePart<N>>,
+}
+
+/// A model of a scene, and how it is placed and named there.
+struct ScenePart<N>
+where
+    N: 'static + Number,
+{
+    model: Box<dyn PipelineBuilder<N>>,
+    transform: Option<Isometry3<f32>>,
+    /// Prepended to the names of the layers of the model.
+    prefix: Option<String>,
+}
+
+impl<N> ScenePart<N>
+where
+    N: 'static + Number,
+{
+    fn new(model: Box<dyn PipelineBuilder<N>>) -> Self {
+        Self {
+            model,
+            transform: None,
+            prefix: None,
+        }
+    }
+
+    fn build(
+        self,
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Box<dyn PipelineRenderer> {
+        let mut renderer = self
+            .model
+            .build(device, texture_format, uniform_bind_group_layout);
+
+        if let Some(transform) = &self.transform {
+            renderer.set_transform(transform);
+        }
+        if let Some(prefix) = &self.prefix {
+            for layer in renderer.layers() {
+                let name = format!("{}/{}", prefix, layer.name());
+                layer.set_name(name);
+            }
+        }
+        renderer
+    }
+}
+
+impl<N> SceneModel<N>
+where
+    N: 'static + Number,
+{
+    pub fn new(model: Box<dyn PipelineBuilder<N>>) -> Self {
+        Self {
+            models: vec![ScenePart::new(model)],
+        }
+    }
+
+    pub fn add(&mut self, model: Box<dyn PipelineBuilder<N>>) {
+        self.models.push(ScenePart::new(model));
+    }
+
+    /// Moves the last added model.
+    pub fn set_transform(&mut self, transform: Isometry3<f32>) {
+        if let Some(last) = self.models.last_mut() {
+            last.transform = Some(transform);
+        }
+    }
+
+    /// Prefixes the names of the layers of the last added model, e.g. `"gt/Trajectory"`.
+    pub fn set_prefix(&mut self, prefix: String) {
+        if let Some(last) = self.models.last_mut() {
+            last.prefix = Some(prefix);
+        }
+    }
+
+    /// Returns a single model as it is, as there is nothing to compose.
+    pub fn into_builder(mut self) -> Box<dyn PipelineBuilder<N>> {
+        let part = &self.models[0];
+        match self.models.len() {
+            1 if part.transform.is_none() && part.prefix.is_none() => {
+                self.models.pop().unwrap().model
+            }
+            _ => Box::new(self),
+        }
+    }
+}
+
+impl<N> PipelineBuilder<N> for SceneModel<N>
+where
+    N: 'static + Number,
+{
+    fn build(
+        self: Box<Self>,
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Box<dyn PipelineRenderer> {
+        let renderers = self
+            .models
+            .into_iter()
+            .map(|part| part.build(device, texture_format, uniform_bind_group_layout))
+            .collect();
+
+        Box::new(SceneRenderer { renderers })
+    }
+}
+
+pub struct SceneRenderer {
+    renderers: Vec<Box<dyn PipelineRenderer>>,
+}
+
+impl PipelineRenderer for SceneRenderer {
+    fn sync(&mut self) {
+        for renderer in &mut self.renderers {
+            renderer.sync();
+        }
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::union_all(self.renderers.iter().map(|r| r.bounds()))
+    }
+
+    /// The latest keyframe of the first model which has any.
+    fn latest_keyframe(&self) -> Option<KeyFrameView> {
+        self.renderers.iter().find_map(|r| r.latest_keyframe())
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        self.renderers.iter_mut().flat_map(|r| r.layers()).collect()
+    }
+
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        Pick::nearest(
+            self.renderers
+                .iter_mut()
+                .enumerate()
+                .map(|(model, r)| r.pick(ray).map(|pick| Pick { model, ..pick })),
+        )
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        for renderer in &mut self.renderers {
+            renderer.prepare(device, queue);
+        }
+    }
+
+    fn render<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        for renderer in &self.renderers {
+            renderer.render(device, queue, render_pass);
+        }
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        for renderer in &self.renderers {
+            renderer.render_overlay(device, queue, render_pass);
+        }
+    }
+}
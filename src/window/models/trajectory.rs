@@ -0,0 +1,146 @@
+use super::super::builder::WindowBuilder;
+use super::super::camera::KeyFrameView;
+use super::super::isometries::{IsometriesBuilder, IsometriesRendener, IsometrySource};
+use super::super::lines::{LineSource, LinesBuilder, LinesRendener};
+use crate::pipes::{
+    Bounds, Layer, Pick, PickRay, PipelineBuilder, PipelineDataBuilder, PipelineRenderer,
+};
+
+use nalgebra::{Isometry3, Point3};
+use slam_cv::Number;
+
+/// Camera poses drawn as frustums, connected by a path.
+#[derive(Clone)]
+pub struct TrajectoryModel {
+    poses: Vec<Isometry3<f32>>,
+}
+
+impl TrajectoryModel {
+    pub fn new(poses: Vec<Isometry3<f32>>) -> Self {
+        Self { poses }
+    }
+}
+
+impl PipelineDataBuilder<f32> for TrajectoryModel {
+    type Builder = Self;
+
+    fn default_window(&self) -> WindowBuilder<f32> {
+        WindowBuilder {
+            title: Some("Trajectory Viewer".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn build_data(self) -> Self::Builder {
+        self
+    }
+}
+
+impl<N> PipelineBuilder<N> for TrajectoryModel
+where
+    N: 'static + Number,
+{
+    fn build(
+        self: Box<Self>,
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> Box<dyn PipelineRenderer> {
+        let trajectory = *self;
+        Box::new(TrajectoryRenderer {
+            lines: LinesBuilder::new(trajectory.clone()).build(
+                device,
+                texture_format,
+                uniform_bind_group_layout,
+            ),
+            isometries: IsometriesBuilder::new(trajectory).build(
+                device,
+                texture_format,
+                uniform_bind_group_layout,
+            ),
+        })
+    }
+}
+
+pub struct TrajectoryRenderer {
+    lines: LinesRendener<f32, TrajectoryModel>,
+    isometries: IsometriesRendener<f32, TrajectoryModel>,
+}
+
+impl PipelineRenderer for TrajectoryRenderer {
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::union_all(vec![self.lines.bounds(), self.isometries.bounds()])
+    }
+
+    fn latest_keyframe(&self) -> Option<KeyFrameView> {
+        self.isometries.latest_keyframe()
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        self.lines
+            .layers()
+            .into_iter()
+            .chain(self.isometries.layers())
+            .collect()
+    }
+
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        self.lines.set_transform(transform);
+        self.isometries.set_transform(transform);
+    }
+
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        self.isometries.pick(ray)
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.lines.prepare(device, queue);
+        self.isometries.prepare(device, queue);
+    }
+
+    fn render<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.lines.render(device, queue, render_pass);
+        self.isometries.render(device, queue, render_pass);
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.lines.render_overlay(device, queue, render_pass);
+        self.isometries.render_overlay(device, queue, render_pass);
+    }
+}
+
+impl LineSource<f32> for TrajectoryModel {
+    const NAME: &'static str = "Path";
+    const STATIC: bool = true;
+
+    fn collect_visual_lines(&self) -> Vec<[Point3<f32>; 2]> {
+        self.poses
+            .windows(2)
+            .map(|p| {
+                [
+                    p[0].translation.vector.into(),
+                    p[1].translation.vector.into(),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl IsometrySource<f32> for TrajectoryModel {
+    const DEPTH: f32 = 0.3;
+    const STATIC: bool = true;
+
+    fn collect_visual_isometries(&self) -> Vec<Isometry3<f32>> {
+        self.poses.clone()
+    }
+}
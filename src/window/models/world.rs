@@ -1,12 +1,18 @@
 use core::marker::PhantomData;
+use std::sync::Arc;
 
 use super::super::builder::WindowBuilder;
-use super::super::camera::{CameraControllerConfig, CameraFrustum};
+use super::super::camera::KeyFrameView;
 use super::super::isometries::{IsometriesBuilder, IsometriesRendener, IsometrySource};
 use super::super::lines::{LineSource, LinesBuilder, LinesRendener};
-use super::super::points::{PointSource, PointsBuilder, PointsRendener};
-use crate::pipes::{PipelineBuilder, PipelineDataBuilder, PipelineRenderer, VertexFormat};
+use super::super::points::{PointSource, PointStyle, PointsBuilder, PointsRendener};
+use super::graph::{EdgeKind, KeyFrameGraph};
+use crate::pipes::{
+    Bounds, Layer, Pick, PickRay, PipelineBuilder, PipelineDataBuilder, PipelineRenderer,
+    VertexFormat,
+};
 
+use image::RgbaImage;
 use nalgebra::{Isometry3, Point3};
 use slam_cv::{feature::Landmark, frame::KeyFrame, vo::World, Number};
 
@@ -20,6 +26,8 @@ where
     W: 'static + World<Number = N, KeyFrame = KF, Landmark = F> + Clone,
 {
     world: W,
+    images: Vec<Option<Arc<RgbaImage>>>,
+    graph: Option<Arc<KeyFrameGraph>>,
 
     _feature: PhantomData<F>,
     _keyframees: PhantomData<KF>,
@@ -34,13 +42,31 @@ where
     W: 'static + World<Number = N, KeyFrame = KF, Landmark = F> + Clone,
 {
     pub fn new(world: W) -> Self {
+        Self::with_images(world, vec![])
+    }
+
+    /// **caution**: `images` should be in the order of the keyframes of the world.
+    pub fn with_images(world: W, images: Vec<Option<RgbaImage>>) -> Self {
         Self {
             world,
+            images: images.into_iter().map(|i| i.map(Arc::new)).collect(),
+            graph: None,
 
             _feature: Default::default(),
             _keyframees: Default::default(),
         }
     }
+
+    /// Draws the edges between the keyframes, in the order of the keyframes of the world.
+    pub fn with_graph(mut self, graph: KeyFrameGraph) -> Self {
+        self.graph = Some(Arc::new(graph));
+        self
+    }
+
+    fn keyframe_positions(&self) -> Vec<Point3<N>> {
+        self.world
+            .collect_keyframes(|kf| kf.isometry().translation.vector.into())
+    }
 }
 
 impl<F, KF, W> PipelineDataBuilder<f32> for WorldModel<f32, F, KF, W>
@@ -55,17 +81,7 @@ where
     fn default_window(&self) -> WindowBuilder<f32> {
         WindowBuilder {
             title: Some("Map Viewer".to_string()),
-            framerate: Some(120),
-
-            camera: CameraFrustum {
-                eye: Point3::new(0., 2., 5.),
-                at: Point3::new(0., 0., 0.),
-
-                fovy: std::f32::consts::FRAC_PI_4,
-                znear: 0.1,
-                zfar: 100.0,
-            },
-            camera_controller: CameraControllerConfig::default(),
+            ..Default::default()
         }
     }
 
@@ -91,6 +107,31 @@ where
         uniform_bind_group_layout: &wgpu::BindGroupLayout,
     ) -> Box<dyn PipelineRenderer> {
         let world = *self;
+
+        // a layer for each kind of the edges, sharing the positions of the keyframes
+        let graph = match &world.graph {
+            Some(graph) => {
+                let positions = Arc::new(world.keyframe_positions());
+                EdgeKind::ALL
+                    .iter()
+                    .filter(|&&kind| graph.has(kind))
+                    .map(|&kind| {
+                        let source = KeyFrameGraphSource {
+                            graph: graph.clone(),
+                            positions: positions.clone(),
+                            kind,
+                        };
+                        LinesBuilder::new(source).build(
+                            device,
+                            texture_format,
+                            uniform_bind_group_layout,
+                        )
+                    })
+                    .collect()
+            }
+            None => vec![],
+        };
+
         Box::new(WorldRenderer {
             points: PointsBuilder::new(world.clone()).build(
                 device,
@@ -102,6 +143,7 @@ where
                 texture_format,
                 uniform_bind_group_layout,
             ),
+            graph,
             isometries: IsometriesBuilder::new(world).build(
                 device,
                 texture_format,
@@ -122,6 +164,7 @@ where
 {
     points: PointsRendener<N, WorldModel<N, F, KF, W>>,
     lines: LinesRendener<N, WorldModel<N, F, KF, W>>,
+    graph: Vec<LinesRendener<N, KeyFrameGraphSource<N>>>,
     isometries: IsometriesRendener<N, WorldModel<N, F, KF, W>>,
 }
 
@@ -134,10 +177,81 @@ where
     W: 'static + World<Number = N, KeyFrame = KF, Landmark = F> + Clone,
     WorldModel<N, F, KF, W>: PointSource<N> + LineSource<N> + IsometrySource<N>,
 {
-    fn render<'a>(&'a mut self, device: &wgpu::Device, render_pass: &mut wgpu::RenderPass<'a>) {
-        self.points.render(device, render_pass);
-        self.lines.render(device, render_pass);
-        self.isometries.render(device, render_pass);
+    fn bounds(&self) -> Option<Bounds> {
+        Bounds::union_all(
+            vec![
+                self.points.bounds(),
+                self.lines.bounds(),
+                self.isometries.bounds(),
+            ]
+            .into_iter()
+            .chain(self.graph.iter().map(|g| g.bounds())),
+        )
+    }
+
+    fn latest_keyframe(&self) -> Option<KeyFrameView> {
+        self.isometries.latest_keyframe()
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        // the optional graph last, so that the others keep their numbers
+        self.points
+            .layers()
+            .into_iter()
+            .chain(self.lines.layers())
+            .chain(self.isometries.layers())
+            .chain(self.graph.iter_mut().flat_map(|g| g.layers()))
+            .collect()
+    }
+
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        self.points.set_transform(transform);
+        self.lines.set_transform(transform);
+        for graph in &mut self.graph {
+            graph.set_transform(transform);
+        }
+        self.isometries.set_transform(transform);
+    }
+
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        Pick::nearest(vec![self.points.pick(ray), self.isometries.pick(ray)])
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.points.prepare(device, queue);
+        self.isometries.prepare(device, queue);
+        self.lines.prepare(device, queue);
+        for graph in &mut self.graph {
+            graph.prepare(device, queue);
+        }
+    }
+
+    fn render<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.points.render(device, queue, render_pass);
+        self.isometries.render(device, queue, render_pass);
+        self.lines.render(device, queue, render_pass);
+        for graph in &self.graph {
+            graph.render(device, queue, render_pass);
+        }
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        self.points.render_overlay(device, queue, render_pass);
+        self.isometries.render_overlay(device, queue, render_pass);
+        self.lines.render_overlay(device, queue, render_pass);
+        for graph in &self.graph {
+            graph.render_overlay(device, queue, render_pass);
+        }
     }
 }
 
@@ -149,6 +263,11 @@ where
     KF: 'static + KeyFrame<Number = N, Feature = F> + Clone,
     W: 'static + World<Number = N, KeyFrame = KF, Landmark = F> + Clone,
 {
+    const NAME: &'static str = "Landmarks";
+    const STYLE: PointStyle = PointStyle::LANDMARKS;
+    // the world is a copy, which never changes
+    const STATIC: bool = true;
+
     fn collect_visual_points(&self) -> Vec<Point3<N>> {
         self.world.collect_landmarks(Landmark::point_world)
     }
@@ -162,6 +281,11 @@ where
     KF: 'static + KeyFrame<Number = N, Feature = F> + Clone,
     W: 'static + World<Number = N, KeyFrame = KF, Landmark = F> + Clone,
 {
+    const NAME: &'static str = "Trajectory";
+    // the trajectory is drawn as an overlay
+    const DEPTH_TEST: bool = false;
+    const STATIC: bool = true;
+
     fn collect_visual_lines(&self) -> Vec<[Point3<N>; 2]> {
         let mut prev = None;
 
@@ -183,9 +307,55 @@ where
     KF: 'static + KeyFrame<Number = f32, Feature = F> + Clone,
     W: 'static + World<Number = f32, KeyFrame = KF, Landmark = F> + Clone,
 {
-    const SIZE: [f32; 2] = [0.2, 0.16];
+    const DEPTH: f32 = 0.3;
+    const STATIC: bool = true;
 
     fn collect_visual_isometries(&self) -> Vec<Isometry3<f32>> {
         self.world.collect_keyframes(KF::isometry)
     }
+
+    fn collect_images(&self) -> Vec<Option<Arc<RgbaImage>>> {
+        self.images.clone()
+    }
+}
+
+/// The edges of a kind between the keyframes of a world.
+#[derive(Clone)]
+pub struct KeyFrameGraphSource<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    graph: Arc<KeyFrameGraph>,
+    positions: Arc<Vec<Point3<N>>>,
+    kind: EdgeKind,
+}
+
+impl<N> LineSource<N> for KeyFrameGraphSource<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    // the edges are drawn as an overlay, like the trajectory
+    const DEPTH_TEST: bool = false;
+    const STATIC: bool = true;
+
+    fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    fn source_color(&self) -> [f32; 3] {
+        self.graph.color(self.kind)
+    }
+
+    fn collect_visual_lines(&self) -> Vec<[Point3<N>; 2]> {
+        self.collect_visual_colored_lines()
+            .into_iter()
+            .map(|(line, _)| line)
+            .collect()
+    }
+
+    fn collect_visual_colored_lines(&self) -> Vec<([Point3<N>; 2], [f32; 3])> {
+        self.graph.collect_lines(self.kind, &self.positions)
+    }
 }
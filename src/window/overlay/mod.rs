@@ -0,0 +1,347 @@
+mod font;
+mod ui;
+
+use self::ui::{Rect, Ui, UiVertex};
+use super::camera::{Camera, FrameClock};
+use super::event::WindowEventState;
+use crate::pipes::{depth_stencil_state, GpuVec, PipelineRenderer, StaticShaderModule};
+
+use slam_cv::Number;
+use winit::event::{ElementState, WindowEvent};
+
+/// The colors a layer cycles through, from its own colors.
+const PALETTE: [(&str, Option<[f32; 3]>); 8] = [
+    ("source", None),
+    ("red", Some([1.0, 0.0, 0.0])),
+    ("green", Some([0.0, 1.0, 0.0])),
+    ("blue", Some([0.0, 0.0, 1.0])),
+    ("yellow", Some([1.0, 1.0, 0.0])),
+    ("cyan", Some([0.0, 1.0, 1.0])),
+    ("magenta", Some([1.0, 0.0, 1.0])),
+    ("white", Some([1.0, 1.0, 1.0])),
+];
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct OverlayUniforms {
+    viewport: [f32; 2],
+    _padding: [f32; 2],
+}
+
+unsafe impl bytemuck::Pod for OverlayUniforms {}
+unsafe impl bytemuck::Zeroable for OverlayUniforms {}
+
+/// The panels over the window: the debug panel with the frame rate, the camera and the layers,
+/// and the legend of the layers.
+pub struct Overlay {
+    pub visible: bool,
+    pub legend: bool,
+
+    render_pipeline: wgpu::RenderPipeline,
+    buffer: GpuVec<UiVertex>,
+
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+
+    /// The areas of the panels last drawn, over which the mouse is captured.
+    panels: Vec<Rect>,
+    cursor: [f32; 2],
+    click: Option<[f32; 2]>,
+    is_captured: bool,
+
+    clock: FrameClock,
+    frame_time: f32,
+}
+
+impl Overlay {
+    pub fn new(device: &wgpu::Device, texture_format: wgpu::TextureFormat) -> Self {
+        let uniforms = OverlayUniforms {
+            viewport: [1.0, 1.0],
+            _padding: [0.0, 0.0],
+        };
+
+        let uniform_buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&[uniforms]),
+            wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        );
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            bindings: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::VERTEX,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            }],
+            label: Some("overlay_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &uniform_buffer,
+                    range: 0..std::mem::size_of_val(&uniforms) as wgpu::BufferAddress,
+                },
+            }],
+            label: Some("overlay_bind_group"),
+        });
+
+        let render_pipeline = build_render_pipeline(device, texture_format, &bind_group_layout);
+
+        Self {
+            visible: false,
+            legend: true,
+
+            render_pipeline,
+            buffer: GpuVec::new(wgpu::BufferUsage::VERTEX),
+
+            uniform_buffer,
+            bind_group,
+
+            panels: vec![],
+            cursor: [0.0, 0.0],
+            click: None,
+            is_captured: false,
+
+            clock: FrameClock::default(),
+            frame_time: 0.0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Takes the mouse over the panels, so that the clicks do not move the camera.
+    pub fn input(&mut self, event: &WindowEvent) -> WindowEventState {
+        let cursor = self.cursor;
+        let is_over = self.panels.iter().any(|p| p.contains(cursor));
+
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = [position.x as f32, position.y as f32];
+                WindowEventState::Unused
+            }
+            WindowEvent::MouseInput { state, .. } => match state {
+                ElementState::Pressed if is_over => {
+                    self.click = Some(self.cursor);
+                    self.is_captured = true;
+                    WindowEventState::Consumed
+                }
+                ElementState::Released if self.is_captured => {
+                    self.is_captured = false;
+                    WindowEventState::Consumed
+                }
+                _ => WindowEventState::Unused,
+            },
+            WindowEvent::MouseWheel { .. } if is_over => WindowEventState::Consumed,
+            _ => WindowEventState::Unused,
+        }
+    }
+
+    /// Lays out the panels, applying the click of this frame to the layers.
+    pub fn update<N>(
+        &mut self,
+        queue: &wgpu::Queue,
+        camera: &Camera<N>,
+        renderer: &mut dyn PipelineRenderer,
+        width: u32,
+        height: u32,
+    ) where
+        N: Number,
+    {
+        // smooth the frame rate, to keep it readable
+        let dt = self.clock.tick();
+        self.frame_time += (dt - self.frame_time) * 0.1;
+
+        let click = self.click.take();
+        let mut vertices = vec![];
+        let mut panels = vec![];
+
+        if self.visible {
+            let ui = self.debug_panel(Ui::new(click), camera, renderer);
+            let (panel_vertices, panel) = ui.finish();
+            vertices.extend(panel_vertices);
+            panels.extend(panel);
+        }
+
+        if self.legend {
+            let ui = legend(width as f32, click, renderer);
+            let (panel_vertices, panel) = ui.finish();
+            vertices.extend(panel_vertices);
+            panels.extend(panel);
+        }
+
+        self.buffer.update(vertices);
+        self.panels = panels;
+
+        let uniforms = OverlayUniforms {
+            viewport: [width.max(1) as f32, height.max(1) as f32],
+            _padding: [0.0, 0.0],
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+    }
+
+    fn debug_panel<N>(
+        &self,
+        mut ui: Ui,
+        camera: &Camera<N>,
+        renderer: &mut dyn PipelineRenderer,
+    ) -> Ui
+    where
+        N: Number,
+    {
+        let cast = |v: N| num::cast::<N, f32>(v).unwrap();
+
+        ui.label(&format!("FPS {:.0}", 1.0 / self.frame_time.max(1e-3)));
+        ui.label(&format!(
+            "Eye {:.2} {:.2} {:.2}",
+            cast(camera.eye.x),
+            cast(camera.eye.y),
+            cast(camera.eye.z),
+        ));
+        ui.label(&format!(
+            "Yaw {:.0} Pitch {:.0}",
+            cast(camera.yaw).to_degrees(),
+            cast(camera.pitch).to_degrees(),
+        ));
+
+        for layer in renderer.layers() {
+            let mut visible = layer.is_visible();
+            let text = format!("{} ({})", layer.name(), layer.count());
+            if ui.checkbox(&text, &mut visible) {
+                layer.set_visible(visible);
+            }
+
+            if let Some(mut style) = layer.point_style() {
+                if ui.stepper("  Size", &mut style.size, 1.0, 1.0..=20.0) {
+                    layer.set_point_style(style);
+                }
+            }
+
+            if let Some(mut scale) = layer.frustum_scale() {
+                if ui.stepper("  Scale", &mut scale, 0.5, 0.5..=8.0) {
+                    layer.set_frustum_scale(scale);
+                }
+            }
+
+            let color = PALETTE
+                .iter()
+                .position(|(_, c)| *c == layer.color())
+                .unwrap_or(0);
+            if ui.button(&format!("  Color {}", PALETTE[color].0)) {
+                layer.set_color(PALETTE[(color + 1) % PALETTE.len()].1);
+            }
+        }
+        ui
+    }
+}
+
+/// The layers with their colors and their keys, which toggle them as well when clicked.
+fn legend(viewport_width: f32, click: Option<[f32; 2]>, renderer: &mut dyn PipelineRenderer) -> Ui {
+    let mut layers = renderer.layers();
+    let texts: Vec<_> = layers
+        .iter()
+        .enumerate()
+        .map(|(index, layer)| match index {
+            // the keys 1 to 9 toggle the first layers
+            0..=8 => format!("{} {}", index + 1, layer.name()),
+            _ => format!("  {}", layer.name()),
+        })
+        .collect();
+
+    let row_width = texts
+        .iter()
+        .map(|text| Ui::swatch_width(text))
+        .fold(0.0, f32::max);
+    let mut ui = Ui::top_right(viewport_width, row_width, click);
+
+    for (layer, text) in layers.iter_mut().zip(&texts) {
+        let color = layer.color().unwrap_or_else(|| layer.source_color());
+        if ui.swatch(text, color, layer.is_visible()) {
+            layer.set_visible(!layer.is_visible());
+        }
+    }
+    ui
+}
+
+impl PipelineRenderer for Overlay {
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        self.buffer.upload(device, queue);
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        self.buffer.set_buffer(render_pass);
+    }
+}
+
+fn build_render_pipeline(
+    device: &wgpu::Device,
+    texture_format: wgpu::TextureFormat,
+    bind_group_layout: &wgpu::BindGroupLayout,
+) -> wgpu::RenderPipeline {
+    const VS_SRC: StaticShaderModule = StaticShaderModule {
+        spirv_source: include_bytes!("shader.vert.spv"),
+        entry_point: None,
+    };
+    const FS_SRC: StaticShaderModule = StaticShaderModule {
+        spirv_source: include_bytes!("shader.frag.spv"),
+        entry_point: None,
+    };
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[&bind_group_layout],
+    });
+
+    // the panel is translucent
+    let blend = wgpu::BlendDescriptor {
+        src_factor: wgpu::BlendFactor::SrcAlpha,
+        dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+        operation: wgpu::BlendOperation::Add,
+    };
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: &render_pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &VS_SRC.build(device),
+            entry_point: VS_SRC.entry_point(),
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &FS_SRC.build(device),
+            entry_point: FS_SRC.entry_point(),
+        }),
+        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: wgpu::CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: texture_format,
+            color_blend: blend.clone(),
+            alpha_blend: blend,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        depth_stencil_state: Some(depth_stencil_state(false)),
+        vertex_state: wgpu::VertexStateDescriptor {
+            index_format: wgpu::IndexFormat::Uint16,
+            vertex_buffers: &[UiVertex::desc(&UiVertex::attributes())],
+        },
+        sample_count: 1,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
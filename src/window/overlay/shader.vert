@@ -0,0 +1,18 @@
+#version 450
+
+layout(location=0)in vec2 a_position;
+layout(location=1)in vec4 a_color;
+
+layout(location=0)out vec4 v_color;
+
+layout(set=0,binding=0)uniform OverlayUniforms{
+    vec2 u_viewport;
+};
+
+void main(){
+    v_color=a_color;
+
+    // pixels from the top left corner to NDC
+    vec2 ndc=a_position/u_viewport*2.-1.;
+    gl_Position=vec4(ndc.x,-ndc.y,0.,1.);
+}
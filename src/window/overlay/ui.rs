@@ -0,0 +1,319 @@
+use core::mem;
+use core::ops::RangeInclusive;
+
+use super::font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::pipes::GpuVertex;
+
+/// The size of a font pixel, in screen pixels.
+const SCALE: f32 = 2.0;
+const LINE_HEIGHT: f32 = (GLYPH_HEIGHT as f32 + 3.0) * SCALE;
+const ADVANCE: f32 = (GLYPH_WIDTH as f32 + 1.0) * SCALE;
+const MARGIN: f32 = 10.0;
+const PADDING: f32 = 8.0;
+/// The size of the boxes of the checkboxes and the buttons.
+const BOX: f32 = GLYPH_HEIGHT as f32 * SCALE;
+
+const BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.6];
+const TEXT: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const WIDGET: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+const DIMMED: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct UiVertex {
+    /// In pixels from the top left corner.
+    pub position: [f32; 2],
+    pub color: [f32; 4],
+}
+
+type Attributes = [wgpu::VertexAttributeDescriptor; 2];
+
+impl UiVertex {
+    pub fn attributes() -> Attributes {
+        [
+            wgpu::VertexAttributeDescriptor {
+                offset: 0,
+                shader_location: 0,
+                format: wgpu::VertexFormat::Float2,
+            },
+            wgpu::VertexAttributeDescriptor {
+                offset: mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                shader_location: 1,
+                format: wgpu::VertexFormat::Float4,
+            },
+        ]
+    }
+
+    pub fn desc(attributes: &Attributes) -> wgpu::VertexBufferDescriptor<'_> {
+        wgpu::VertexBufferDescriptor {
+            stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes,
+        }
+    }
+}
+
+impl GpuVertex for UiVertex {
+    fn weight() -> u64 {
+        1
+    }
+}
+
+unsafe impl bytemuck::Pod for UiVertex {}
+unsafe impl bytemuck::Zeroable for UiVertex {}
+
+/// A rectangle in pixels from the top left corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+impl Rect {
+    pub fn contains(&self, point: [f32; 2]) -> bool {
+        (0..2).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+}
+
+/// An immediate mode panel, laid out anew each frame from its top left corner.
+///
+/// Each widget takes a row, and returns whether the click of this frame changed it.
+pub struct Ui {
+    vertices: Vec<UiVertex>,
+    click: Option<[f32; 2]>,
+
+    origin: [f32; 2],
+    row: f32,
+    width: f32,
+}
+
+impl Ui {
+    /// A panel in the top left corner of the window.
+    pub fn new(click: Option<[f32; 2]>) -> Self {
+        Self::at([MARGIN, MARGIN], click)
+    }
+
+    /// A panel in the top right corner of the window, for rows up to `row_width` wide.
+    pub fn top_right(viewport_width: f32, row_width: f32, click: Option<[f32; 2]>) -> Self {
+        let x = viewport_width - MARGIN - row_width - PADDING * 2.0;
+        Self::at([x, MARGIN], click)
+    }
+
+    pub fn at(origin: [f32; 2], click: Option<[f32; 2]>) -> Self {
+        Self {
+            vertices: vec![],
+            click,
+
+            origin,
+            row: origin[1] + PADDING,
+            width: origin[0],
+        }
+    }
+
+    /// The width of a `swatch` row.
+    pub fn swatch_width(text: &str) -> f32 {
+        BOX + ADVANCE + text.chars().count() as f32 * ADVANCE - SCALE
+    }
+
+    pub fn label(&mut self, text: &str) {
+        let x = self.left();
+        let end = self.text(x, text, TEXT);
+        self.end_row(end);
+    }
+
+    /// A box, filled when `checked`, and its label; the whole row toggles it.
+    pub fn checkbox(&mut self, text: &str, checked: &mut bool) -> bool {
+        let x = self.left();
+        let square = self.square(x);
+        self.outline(square, WIDGET);
+        if *checked {
+            let inner = Rect {
+                min: [square.min[0] + SCALE * 2.0, square.min[1] + SCALE * 2.0],
+                max: [square.max[0] - SCALE * 2.0, square.max[1] - SCALE * 2.0],
+            };
+            self.rect(inner, TEXT);
+        }
+
+        let end = self.text(square.max[0] + ADVANCE, text, TEXT);
+        let row = self.row_rect(end);
+        self.end_row(end);
+
+        let clicked = self.clicked(row);
+        if clicked {
+            *checked = !*checked;
+        }
+        clicked
+    }
+
+    /// A box filled with the color and its label, dimmed when disabled; the whole row is clicked.
+    pub fn swatch(&mut self, text: &str, color: [f32; 3], enabled: bool) -> bool {
+        let x = self.left();
+        let square = self.square(x);
+        if enabled {
+            self.rect(square, [color[0], color[1], color[2], 1.0]);
+        } else {
+            self.outline(square, [color[0], color[1], color[2], 1.0]);
+        }
+
+        let text_color = if enabled { TEXT } else { DIMMED };
+        let end = self.text(square.max[0] + ADVANCE, text, text_color);
+        let row = self.row_rect(end);
+        self.end_row(end);
+
+        self.clicked(row)
+    }
+
+    /// A value with `-` and `+` buttons, changing it by `step` within `range`.
+    pub fn stepper(
+        &mut self,
+        text: &str,
+        value: &mut f32,
+        step: f32,
+        range: RangeInclusive<f32>,
+    ) -> bool {
+        let x = self.left();
+        let x = self.text(x, text, TEXT) + ADVANCE;
+
+        let minus = self.button_box(x, "-");
+        let x = self.text(minus.max[0] + ADVANCE, &format!("{:.1}", value), TEXT);
+        let plus = self.button_box(x + ADVANCE, "+");
+        self.end_row(plus.max[0]);
+
+        let delta = if self.clicked(minus) {
+            -step
+        } else if self.clicked(plus) {
+            step
+        } else {
+            return false;
+        };
+
+        let new = (*value + delta).max(*range.start()).min(*range.end());
+        let changed = new != *value;
+        *value = new;
+        changed
+    }
+
+    /// A label in a box, which is clicked.
+    pub fn button(&mut self, text: &str) -> bool {
+        let x = self.left();
+        let end = self.text(x + SCALE * 2.0, text, TEXT) + SCALE;
+        let rect = Rect {
+            min: [x, self.row - SCALE],
+            max: [end, self.row + BOX + SCALE],
+        };
+        self.outline(rect, WIDGET);
+        self.end_row(end);
+
+        self.clicked(rect)
+    }
+
+    /// The vertices, behind which the panel is drawn, and the area of the panel.
+    pub fn finish(self) -> (Vec<UiVertex>, Option<Rect>) {
+        if self.vertices.is_empty() {
+            return (self.vertices, None);
+        }
+
+        let panel = Rect {
+            min: self.origin,
+            max: [self.width + PADDING, self.row - LINE_HEIGHT + BOX + PADDING],
+        };
+
+        let mut vertices = quad(panel, BACKGROUND).to_vec();
+        vertices.extend(self.vertices);
+        (vertices, Some(panel))
+    }
+
+    fn left(&self) -> f32 {
+        self.origin[0] + PADDING
+    }
+
+    fn end_row(&mut self, end: f32) {
+        self.width = self.width.max(end);
+        self.row += LINE_HEIGHT;
+    }
+
+    fn row_rect(&self, end: f32) -> Rect {
+        Rect {
+            min: [self.left(), self.row],
+            max: [end, self.row + BOX],
+        }
+    }
+
+    fn square(&self, x: f32) -> Rect {
+        Rect {
+            min: [x, self.row],
+            max: [x + BOX, self.row + BOX],
+        }
+    }
+
+    fn button_box(&mut self, x: f32, text: &str) -> Rect {
+        let square = self.square(x);
+        self.outline(square, WIDGET);
+        self.text(x + SCALE, text, TEXT);
+        square
+    }
+
+    /// Takes the click of this frame if it is in the rectangle.
+    fn clicked(&mut self, rect: Rect) -> bool {
+        match self.click {
+            Some(click) if rect.contains(click) => {
+                self.click = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Draws the text from the left, returning its right end.
+    fn text(&mut self, x: f32, text: &str, color: [f32; 4]) -> f32 {
+        let mut left = x;
+        for c in text.chars() {
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                        let min = [left + column as f32 * SCALE, self.row + row as f32 * SCALE];
+                        let max = [min[0] + SCALE, min[1] + SCALE];
+                        self.rect(Rect { min, max }, color);
+                    }
+                }
+            }
+            left += ADVANCE;
+        }
+        left - SCALE
+    }
+
+    fn outline(&mut self, rect: Rect, color: [f32; 4]) {
+        let Rect { min, max } = rect;
+        let edges = [
+            (min, [max[0], min[1] + SCALE]),
+            ([min[0], max[1] - SCALE], max),
+            (min, [min[0] + SCALE, max[1]]),
+            ([max[0] - SCALE, min[1]], max),
+        ];
+        for &(min, max) in edges.iter() {
+            self.rect(Rect { min, max }, color);
+        }
+    }
+
+    fn rect(&mut self, rect: Rect, color: [f32; 4]) {
+        self.vertices.extend_from_slice(&quad(rect, color));
+    }
+}
+
+/// Two triangles covering the rectangle.
+fn quad(rect: Rect, color: [f32; 4]) -> [UiVertex; 6] {
+    let Rect { min, max } = rect;
+    let vertex = |x, y| UiVertex {
+        position: [x, y],
+        color,
+    };
+
+    [
+        vertex(min[0], min[1]),
+        vertex(max[0], min[1]),
+        vertex(max[0], max[1]),
+        vertex(min[0], min[1]),
+        vertex(max[0], max[1]),
+        vertex(min[0], max[1]),
+    ]
+}
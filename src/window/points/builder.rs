@@ -3,7 +3,8 @@ use core::marker::PhantomData;
 use super::point::Point;
 use super::renderer::PointsRendener;
 use super::source::PointSource;
-use crate::pipes::{GpuVec, StaticShaderModule, VertexFormat};
+use super::splat::Splat;
+use crate::pipes::{depth_stencil_state, GpuVec, StaticShaderModule, VertexFormat};
 
 use nalgebra::Point3;
 use slam_cv::Number;
@@ -39,12 +40,31 @@ where
         texture_format: wgpu::TextureFormat,
         uniform_bind_group_layout: &wgpu::BindGroupLayout,
     ) -> PointsRendener<N, S> {
-        let render_pipeline =
-            build_render_pipeline(device, texture_format, uniform_bind_group_layout);
+        let render_pipeline = build_render_pipeline(
+            device,
+            texture_format,
+            uniform_bind_group_layout,
+            S::DEPTH_TEST,
+        );
+
+        let splat = Splat::new::<N>(
+            device,
+            texture_format,
+            uniform_bind_group_layout,
+            S::DEPTH_TEST,
+            S::STYLE,
+        );
 
         PointsRendener {
             render_pipeline,
+            splat,
             buffer: GpuVec::new(wgpu::BufferUsage::VERTEX),
+            version: None,
+            name: self.source.name().to_string(),
+            pick_index: None,
+            visible: true,
+            color: None,
+            transform: None,
 
             number: Default::default(),
             source: self.source,
@@ -56,6 +76,7 @@ pub fn build_render_pipeline(
     device: &wgpu::Device,
     texture_format: wgpu::TextureFormat,
     uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    depth_test: bool,
 ) -> wgpu::RenderPipeline {
     const VS_SRC: StaticShaderModule = StaticShaderModule {
         spirv_source: include_bytes!("shader.vert.spv"),
@@ -94,7 +115,7 @@ pub fn build_render_pipeline(
             write_mask: wgpu::ColorWrite::ALL,
         }],
         primitive_topology: wgpu::PrimitiveTopology::PointList,
-        depth_stencil_state: None,
+        depth_stencil_state: Some(depth_stencil_state(depth_test)),
         vertex_state: wgpu::VertexStateDescriptor {
             index_format: wgpu::IndexFormat::Uint16,
             vertex_buffers: &[Point::desc(&Point::attributes())],
@@ -1,11 +1,15 @@
 mod builder;
 mod renderer;
 mod source;
+mod splat;
+mod style;
 
 mod point;
 
 pub use self::builder::{build_render_pipeline, PointsBuilder};
 pub use self::renderer::PointsRendener;
 pub use self::source::PointSource;
+pub use self::splat::Splat;
+pub use self::style::{PointShape, PointStyle};
 
 pub use self::point::Point;
@@ -58,6 +58,15 @@ where
             attributes,
         }
     }
+
+    /// Steps once per instance, e.g. per splat.
+    pub fn instance_desc(attributes: &Attributes) -> wgpu::VertexBufferDescriptor<'_> {
+        wgpu::VertexBufferDescriptor {
+            stride: mem::size_of::<Self>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes,
+        }
+    }
 }
 
 impl<N> GpuVertex for Point<N>
@@ -3,9 +3,14 @@ use core::marker::PhantomData;
 use super::builder::PointsBuilder;
 use super::point::Point;
 use super::source::PointSource;
-use crate::pipes::{GpuVec, PipelineBuilder, PipelineRenderer, VertexFormat};
+use super::splat::Splat;
+use super::style::{PointShape, PointStyle};
+use crate::pipes::{
+    cast_isometry, Bounds, GpuVec, Layer, Pick, PickIndex, PickKind, PickRay, PipelineBuilder,
+    PipelineRenderer, VertexFormat,
+};
 
-use nalgebra::Point3;
+use nalgebra::{Isometry3, Point3};
 use slam_cv::Number;
 
 pub struct PointsRendener<N, S>
@@ -15,12 +20,54 @@ where
     S: 'static + PointSource<N>,
 {
     pub render_pipeline: wgpu::RenderPipeline,
+    pub splat: Splat,
     pub buffer: GpuVec<Point<N>>,
+    pub version: Option<u64>,
+    /// The name of the layer, the one of the source unless renamed.
+    pub name: String,
+    /// Built on the first pick after each update.
+    pub pick_index: Option<PickIndex>,
+    pub visible: bool,
+    /// Paints all the elements instead of their own colors, if set.
+    pub color: Option<[f32; 3]>,
+    /// Moves all the elements, if set.
+    pub transform: Option<Isometry3<N>>,
 
     pub number: PhantomData<N>,
     pub source: S,
 }
 
+impl<N, S> PointsRendener<N, S>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    S: 'static + PointSource<N>,
+{
+    pub fn style(&self) -> PointStyle {
+        self.splat.style()
+    }
+
+    pub fn set_style(&mut self, style: PointStyle) {
+        self.splat.set_style(style);
+    }
+
+    fn draw<'a>(&'a self, render_pass: &mut wgpu::RenderPass<'a>) {
+        if !self.visible {
+            return;
+        }
+
+        match self.splat.style().shape {
+            PointShape::Pixel => {
+                render_pass.set_pipeline(&self.render_pipeline);
+                self.buffer.set_buffer(render_pass);
+            }
+            PointShape::Square | PointShape::Round => {
+                self.splat.render(render_pass, &self.buffer);
+            }
+        }
+    }
+}
+
 impl<N, S> PipelineBuilder<N> for PointsBuilder<N, S>
 where
     Self: Send,
@@ -44,19 +91,131 @@ where
     Point3<N>: VertexFormat<N>,
     S: PointSource<N>,
 {
-    fn render<'a>(&'a mut self, device: &wgpu::Device, render_pass: &mut wgpu::RenderPass<'a>) {
-        render_pass.set_pipeline(&self.render_pipeline);
+    fn bounds(&self) -> Option<Bounds> {
+        // a few stray landmarks should not shrink the whole map
+        const OUTLIERS: f32 = 0.01;
+
+        Bounds::from_points(self.buffer.as_slice().iter().map(|p| p.position), OUTLIERS)
+    }
+
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        vec![self as &mut dyn Layer]
+    }
+
+    fn set_transform(&mut self, transform: &Isometry3<f32>) {
+        self.transform = Some(cast_isometry(transform));
+        // collect again with the new transform
+        self.version = None;
+    }
+
+    fn pick(&mut self, ray: &PickRay) -> Option<Pick> {
+        // what is hidden is not under the cursor
+        if !self.visible {
+            return None;
+        }
+
+        let buffer = &self.buffer;
+        self.pick_index
+            .get_or_insert_with(|| {
+                let points = buffer.as_slice().iter().map(|p| p.position);
+                PickIndex::new(PickKind::Landmark, points)
+            })
+            .pick(ray)
+    }
+
+    fn prepare(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let version = self.source.version();
+        if version.is_none() || version != self.version {
+            let points = self
+                .source
+                .collect_visual_colored_points()
+                .into_iter()
+                .map(|(position, color)| Point {
+                    position: match &self.transform {
+                        Some(transform) => transform * position,
+                        None => position,
+                    },
+                    color: self.color.unwrap_or(color).into(),
+                })
+                .collect();
+
+            self.buffer.update(points);
+            self.version = version;
+            self.pick_index = None;
+        }
+
+        self.buffer.upload(device, queue);
+        self.splat.upload(queue);
+    }
+
+    fn render<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        if S::DEPTH_TEST {
+            self.draw(render_pass);
+        }
+    }
+
+    fn render_overlay<'a>(
+        &'a self,
+        _device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        render_pass: &mut wgpu::RenderPass<'a>,
+    ) {
+        if !S::DEPTH_TEST {
+            self.draw(render_pass);
+        }
+    }
+}
+
+impl<N, S> Layer for PointsRendener<N, S>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+    S: PointSource<N>,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn count(&self) -> usize {
+        self.buffer.len()
+    }
 
-        let color = S::COLOR.into();
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
 
-        let points = self
-            .source
-            .collect_visual_points()
-            .into_iter()
-            .map(|p| Point { position: p, color })
-            .collect();
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn color(&self) -> Option<[f32; 3]> {
+        self.color
+    }
+
+    fn set_color(&mut self, color: Option<[f32; 3]>) {
+        self.color = color;
+        // collect again with the new colors
+        self.version = None;
+    }
+
+    fn source_color(&self) -> [f32; 3] {
+        self.source.source_color()
+    }
+
+    fn point_style(&self) -> Option<PointStyle> {
+        Some(self.style())
+    }
 
-        self.buffer.update(device, points);
-        self.buffer.set_buffer(render_pass);
+    fn set_point_style(&mut self, style: PointStyle) {
+        self.set_style(style);
     }
 }
@@ -1,3 +1,4 @@
+use super::style::PointStyle;
 use crate::pipes::VertexFormat;
 
 use nalgebra::Point3;
@@ -8,7 +9,51 @@ where
     N: 'static + Number,
     Point3<N>: VertexFormat<N>,
 {
+    /// The name of the layer, e.g. in the overlay.
+    const NAME: &'static str = "Points";
     const COLOR: [f32; 3] = Colors::red();
+    /// Set `false` to draw as an overlay, ignoring the depth of the others.
+    ///
+    /// The overlays of all the models of a window are drawn after the data tested against the
+    /// depth, so that it does not paint over them.
+    const DEPTH_TEST: bool = true;
+    /// How the points are drawn, single pixels unless a source opts in to splats.
+    const STYLE: PointStyle = PointStyle::PIXEL;
+    /// Set `true` if the data never changes, so that it is collected only once.
+    const STATIC: bool = false;
+
+    /// The name of the layer, which is `NAME` unless it is chosen at runtime.
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    /// The color of the layer in the legend, which is `COLOR` unless it is chosen at runtime.
+    fn source_color(&self) -> [f32; 3] {
+        Self::COLOR
+    }
 
     fn collect_visual_points(&self) -> Vec<Point3<N>>;
+
+    /// Collects the points with their own colors, e.g. from a `Colormap`.
+    ///
+    /// By default, every point is painted with `source_color`.
+    fn collect_visual_colored_points(&self) -> Vec<(Point3<N>, [f32; 3])> {
+        let color = self.source_color();
+        self.collect_visual_points()
+            .into_iter()
+            .map(|p| (p, color))
+            .collect()
+    }
+
+    /// A counter which changes whenever the collected data changes.
+    ///
+    /// `None` means the data may change at any time, so it is collected every frame.
+    /// By default, this is `None` unless the source is `STATIC`.
+    fn version(&self) -> Option<u64> {
+        if Self::STATIC {
+            Some(0)
+        } else {
+            None
+        }
+    }
 }
@@ -0,0 +1,18 @@
+#version 450
+
+layout(location=0)in vec3 v_color;
+layout(location=1)in vec2 v_offset;
+layout(location=0)out vec4 f_color;
+
+layout(set=1,binding=0)uniform SplatUniforms{
+    float u_size;
+    float u_round;
+    float u_attenuation;
+};
+
+void main(){
+    if(u_round>.5&&dot(v_offset,v_offset)>1.){
+        discard;
+    }
+    f_color=vec4(v_color,1.);
+}
@@ -0,0 +1,194 @@
+use super::point::Point;
+use super::style::{PointShape, PointStyle};
+use crate::pipes::{depth_stencil_state, GpuVec, StaticShaderModule, VertexFormat};
+
+use nalgebra::Point3;
+use slam_cv::Number;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct SplatUniforms {
+    size: f32,
+    round: f32,
+    attenuation: f32,
+    _padding: f32,
+}
+
+impl From<PointStyle> for SplatUniforms {
+    fn from(style: PointStyle) -> Self {
+        let flag = |b| if b { 1.0 } else { 0.0 };
+
+        Self {
+            size: style.size,
+            round: flag(style.shape == PointShape::Round),
+            attenuation: flag(style.attenuation),
+            _padding: 0.0,
+        }
+    }
+}
+
+unsafe impl bytemuck::Pod for SplatUniforms {}
+unsafe impl bytemuck::Zeroable for SplatUniforms {}
+
+/// Draws each point as a screen-space quad.
+pub struct Splat {
+    render_pipeline: wgpu::RenderPipeline,
+
+    style: PointStyle,
+    is_style_dirty: bool,
+
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Splat {
+    pub fn new<N>(
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+        depth_test: bool,
+        style: PointStyle,
+    ) -> Self
+    where
+        N: 'static + Number,
+        Point3<N>: VertexFormat<N>,
+    {
+        let uniforms = SplatUniforms::from(style);
+
+        let uniform_buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&[uniforms]),
+            wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        );
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            bindings: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            }],
+            label: Some("splat_bind_group_layout"),
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &bind_group_layout,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &uniform_buffer,
+                    range: 0..std::mem::size_of_val(&uniforms) as wgpu::BufferAddress,
+                },
+            }],
+            label: Some("splat_bind_group"),
+        });
+
+        let render_pipeline = build_splat_pipeline::<N>(
+            device,
+            texture_format,
+            uniform_bind_group_layout,
+            &bind_group_layout,
+            depth_test,
+        );
+
+        Self {
+            render_pipeline,
+
+            style,
+            is_style_dirty: false,
+
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    pub fn style(&self) -> PointStyle {
+        self.style
+    }
+
+    pub fn set_style(&mut self, style: PointStyle) {
+        if self.style != style {
+            self.style = style;
+            self.is_style_dirty = true;
+        }
+    }
+
+    /// Writes the changed style to the uniforms.
+    pub fn upload(&mut self, queue: &wgpu::Queue) {
+        if self.is_style_dirty {
+            let uniforms = SplatUniforms::from(self.style);
+            queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+            self.is_style_dirty = false;
+        }
+    }
+
+    pub fn render<'a, N>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        buffer: &'a GpuVec<Point<N>>,
+    ) where
+        N: 'static + Number,
+        Point3<N>: VertexFormat<N>,
+    {
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+        buffer.set_instance_buffer(render_pass, 6);
+    }
+}
+
+fn build_splat_pipeline<N>(
+    device: &wgpu::Device,
+    texture_format: wgpu::TextureFormat,
+    uniform_bind_group_layout: &wgpu::BindGroupLayout,
+    splat_bind_group_layout: &wgpu::BindGroupLayout,
+    depth_test: bool,
+) -> wgpu::RenderPipeline
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    const VS_SRC: StaticShaderModule = StaticShaderModule {
+        spirv_source: include_bytes!("splat.vert.spv"),
+        entry_point: None,
+    };
+    const FS_SRC: StaticShaderModule = StaticShaderModule {
+        spirv_source: include_bytes!("splat.frag.spv"),
+        entry_point: None,
+    };
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[&uniform_bind_group_layout, &splat_bind_group_layout],
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        layout: &render_pipeline_layout,
+        vertex_stage: wgpu::ProgrammableStageDescriptor {
+            module: &VS_SRC.build(device),
+            entry_point: VS_SRC.entry_point(),
+        },
+        fragment_stage: Some(wgpu::ProgrammableStageDescriptor {
+            module: &FS_SRC.build(device),
+            entry_point: FS_SRC.entry_point(),
+        }),
+        rasterization_state: Some(wgpu::RasterizationStateDescriptor {
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: wgpu::CullMode::None,
+            depth_bias: 0,
+            depth_bias_slope_scale: 0.0,
+            depth_bias_clamp: 0.0,
+        }),
+        color_states: &[wgpu::ColorStateDescriptor {
+            format: texture_format,
+            color_blend: wgpu::BlendDescriptor::REPLACE,
+            alpha_blend: wgpu::BlendDescriptor::REPLACE,
+            write_mask: wgpu::ColorWrite::ALL,
+        }],
+        primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+        depth_stencil_state: Some(depth_stencil_state(depth_test)),
+        vertex_state: wgpu::VertexStateDescriptor {
+            index_format: wgpu::IndexFormat::Uint16,
+            vertex_buffers: &[Point::<N>::instance_desc(&Point::<N>::attributes())],
+        },
+        sample_count: 1,
+        sample_mask: !0,
+        alpha_to_coverage_enabled: false,
+    })
+}
@@ -0,0 +1,38 @@
+#version 450
+
+layout(location=0)in vec3 a_position;
+layout(location=1)in vec3 a_color;
+
+layout(location=0)out vec3 v_color;
+layout(location=1)out vec2 v_offset;
+
+layout(set=0,binding=0)uniform Uniforms{
+    mat4 u_view_proj;
+    vec2 u_viewport;
+};
+
+layout(set=1,binding=0)uniform SplatUniforms{
+    float u_size;
+    float u_round;
+    float u_attenuation;
+};
+
+const vec2 CORNERS[6]=vec2[6](
+    vec2(-1.,-1.),vec2(1.,-1.),vec2(1.,1.),
+    vec2(-1.,-1.),vec2(1.,1.),vec2(-1.,1.)
+);
+
+void main(){
+    vec2 corner=CORNERS[gl_VertexIndex];
+    vec4 center=u_view_proj*vec4(a_position,1.);
+
+    float size=u_size;
+    if(u_attenuation>.5){
+        size/=max(center.w,.001);
+    }
+
+    v_color=a_color;
+    v_offset=corner;
+    // pixels to NDC, premultiplied by w for the perspective division
+    gl_Position=center+vec4(corner*size/u_viewport*center.w,0.,0.);
+}
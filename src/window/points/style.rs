@@ -0,0 +1,49 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PointShape {
+    /// A single pixel per point, which is the cheapest.
+    Pixel,
+    Square,
+    Round,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PointStyle {
+    pub shape: PointShape,
+    /// The diameter in pixels, ignored by `PointShape::Pixel`.
+    pub size: f32,
+    /// Shrinks the distant points, as if `size` were measured at a unit distance.
+    pub attenuation: bool,
+}
+
+impl PointStyle {
+    pub const PIXEL: Self = Self {
+        shape: PointShape::Pixel,
+        size: 1.0,
+        attenuation: false,
+    };
+
+    /// Round splats for the landmarks of a map, which are too sparse to be seen as pixels.
+    pub const LANDMARKS: Self = Self::round(3.0);
+
+    pub const fn round(size: f32) -> Self {
+        Self {
+            shape: PointShape::Round,
+            size,
+            attenuation: false,
+        }
+    }
+
+    pub const fn square(size: f32) -> Self {
+        Self {
+            shape: PointShape::Square,
+            size,
+            attenuation: false,
+        }
+    }
+}
+
+impl Default for PointStyle {
+    fn default() -> Self {
+        Self::PIXEL
+    }
+}
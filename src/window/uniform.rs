@@ -2,7 +2,7 @@ use core::fmt::Debug;
 
 use super::camera::Camera;
 
-use nalgebra::Matrix4;
+use nalgebra::{Matrix4, Vector2};
 use slam_cv::Number;
 
 #[repr(C)]
@@ -12,6 +12,8 @@ where
     N: 'static + Number,
 {
     view_proj: Matrix4<N>,
+    viewport: Vector2<N>,
+    _padding: Vector2<N>,
 }
 
 impl<N> Default for Uniforms<N>
@@ -21,6 +23,8 @@ where
     fn default() -> Self {
         Self {
             view_proj: Matrix4::identity(),
+            viewport: Vector2::new(N::one(), N::one()),
+            _padding: Vector2::zeros(),
         }
     }
 }
@@ -32,7 +36,89 @@ where
     pub fn update_view_proj(&mut self, camera: &Camera<N>, aspect: N) {
         self.view_proj = camera.compute_view_proj(aspect);
     }
+
+    pub fn set_view_proj(&mut self, view_proj: Matrix4<N>) {
+        self.view_proj = view_proj;
+    }
+
+    /// The size of the render target in pixels.
+    pub fn update_viewport(&mut self, width: N, height: N) {
+        self.viewport = Vector2::new(width, height);
+    }
 }
 
 unsafe impl<N> bytemuck::Pod for Uniforms<N> where N: 'static + Number {}
 unsafe impl<N> bytemuck::Zeroable for Uniforms<N> where N: 'static + Number {}
+
+/// The layout of the uniforms, shared by the pipelines and the viewports of a window.
+pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        bindings: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStage::VERTEX,
+            ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+        }],
+        label: Some("uniform_bind_group_layout"),
+    })
+}
+
+/// The uniforms shared by all the pipelines of a render target, bound to the set 0.
+pub struct UniformBuffer<N>
+where
+    N: 'static + Number,
+{
+    pub uniforms: Uniforms<N>,
+
+    buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl<N> UniformBuffer<N>
+where
+    N: 'static + Number,
+{
+    /// Binds the uniforms with the layout of `create_bind_group_layout`,
+    /// which the pipelines drawn with them are built with.
+    pub fn new(
+        device: &wgpu::Device,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        uniforms: Uniforms<N>,
+    ) -> Self {
+        let buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&[uniforms]),
+            wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        );
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: bind_group_layout,
+            bindings: &[wgpu::Binding {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer {
+                    buffer: &buffer,
+                    // FYI: you can share a single buffer between bindings.
+                    range: 0..std::mem::size_of_val(&uniforms) as wgpu::BufferAddress,
+                },
+            }],
+            label: Some("uniform_bind_group"),
+        });
+
+        Self {
+            uniforms,
+
+            buffer,
+            bind_group,
+        }
+    }
+
+    pub fn update(&mut self, camera: &Camera<N>, width: u32, height: u32) {
+        let width = N::from(width).unwrap();
+        let height = N::from(height).unwrap();
+
+        self.uniforms.update_view_proj(camera, width / height);
+        self.uniforms.update_viewport(width, height);
+    }
+
+    pub fn upload(&self, queue: &wgpu::Queue) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[self.uniforms]));
+    }
+}
@@ -0,0 +1,287 @@
+use super::camera::{
+    Camera, CameraController, CameraControllerConfig, CameraMode, CameraTracking, Projection,
+    ViewPreset,
+};
+use super::gizmo::AxisGizmo;
+use super::uniform::{UniformBuffer, Uniforms};
+use crate::pipes::{PipelineRenderer, VertexFormat};
+
+use nalgebra::{Point3, Vector2, Vector3};
+use slam_cv::Number;
+
+/// The camera a viewport starts with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ViewportCamera {
+    /// The camera of the window.
+    Main,
+    /// An orthographic camera looking from the preset.
+    Preset(ViewPreset),
+    /// Looking through the latest keyframe.
+    KeyFrame,
+}
+
+/// How a window is split into viewports, each with its own camera.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ViewLayout {
+    Single,
+    /// Two viewports side by side.
+    Split([ViewportCamera; 2]),
+    /// Four viewports, two by two, from the top left.
+    Quad([ViewportCamera; 4]),
+}
+
+impl Default for ViewLayout {
+    fn default() -> Self {
+        Self::Single
+    }
+}
+
+impl ViewLayout {
+    /// The camera of the window beside a top view.
+    pub const SPLIT: Self = Self::Split([
+        ViewportCamera::Main,
+        ViewportCamera::Preset(ViewPreset::Top),
+    ]);
+
+    /// The camera of the window, the top and front views, and the latest keyframe.
+    pub const QUAD: Self = Self::Quad([
+        ViewportCamera::Main,
+        ViewportCamera::Preset(ViewPreset::Top),
+        ViewportCamera::Preset(ViewPreset::Front),
+        ViewportCamera::KeyFrame,
+    ]);
+
+    pub fn cameras(&self) -> Vec<ViewportCamera> {
+        match self {
+            Self::Single => vec![ViewportCamera::Main],
+            Self::Split(cameras) => cameras.to_vec(),
+            Self::Quad(cameras) => cameras.to_vec(),
+        }
+    }
+
+    /// The areas of the viewports in a window of the size, in the order of `cameras`.
+    pub fn rects(&self, width: u32, height: u32) -> Vec<ViewportRect> {
+        let (columns, rows) = match self {
+            Self::Single => (1, 1),
+            Self::Split(_) => (2, 1),
+            Self::Quad(_) => (2, 2),
+        };
+
+        // the last column and row take the odd pixels
+        let cell = |i: u32, count: u32, size: u32| {
+            let start = size * i / count;
+            let end = size * (i + 1) / count;
+            (start, (end - start).max(1))
+        };
+
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let (x, width) = cell(column, columns, width);
+                let (y, height) = cell(row, rows, height);
+                ViewportRect {
+                    x,
+                    y,
+                    width,
+                    height,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A part of a render target, in pixels from the top left corner.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ViewportRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ViewportRect {
+    pub fn contains(&self, point: Vector2<f64>) -> bool {
+        let (x, y) = (self.x as f64, self.y as f64);
+        x <= point.x
+            && point.x < x + self.width as f64
+            && y <= point.y
+            && point.y < y + self.height as f64
+    }
+
+    /// The point relative to the top left corner of the viewport.
+    pub fn local(&self, point: Vector2<f64>) -> Vector2<f64> {
+        point - Vector2::new(self.x as f64, self.y as f64)
+    }
+
+    pub fn size(&self) -> Vector2<f64> {
+        Vector2::new(self.width as f64, self.height as f64)
+    }
+}
+
+/// A camera drawing the pipelines in a part of the window.
+pub struct Viewport<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    pub rect: ViewportRect,
+
+    pub camera: Camera<N>,
+    pub camera_controller: CameraController<N>,
+
+    pub uniforms: UniformBuffer<N>,
+    pub gizmo: AxisGizmo<N>,
+}
+
+impl<N> Viewport<N>
+where
+    N: 'static + Number,
+    Point3<N>: VertexFormat<N>,
+{
+    pub fn new(
+        device: &wgpu::Device,
+        texture_format: wgpu::TextureFormat,
+        uniform_bind_group_layout: &wgpu::BindGroupLayout,
+        rect: ViewportRect,
+        kind: ViewportCamera,
+        mut camera: Camera<N>,
+        mut config: CameraControllerConfig<N>,
+    ) -> Self {
+        match kind {
+            ViewportCamera::Main => {}
+            ViewportCamera::Preset(preset) => {
+                let (yaw, pitch) = preset.orientation();
+                camera.orbit_to(yaw, pitch);
+                camera.set_projection(Projection::Orthographic);
+            }
+            ViewportCamera::KeyFrame => config.tracking = CameraTracking::ThroughKeyFrame,
+        }
+
+        let camera_controller = config.into();
+        let uniforms = UniformBuffer::new(device, uniform_bind_group_layout, Uniforms::default());
+        let gizmo = AxisGizmo::new(device, texture_format);
+
+        let mut viewport = Self {
+            rect,
+
+            camera,
+            camera_controller,
+
+            uniforms,
+            gizmo,
+        };
+        viewport.resize(rect);
+        viewport
+    }
+
+    pub fn resize(&mut self, rect: ViewportRect) {
+        self.rect = rect;
+        self.camera_controller.window_size =
+            Vector2::new(N::from(rect.width).unwrap(), N::from(rect.height).unwrap());
+    }
+
+    /// Moves the camera for a frame, returning how far the user moved it.
+    ///
+    /// Only the moves of the point the camera turns around count, so that the turns are not
+    /// followed by the linked cameras: the eye of a first person camera, or the pivot.
+    pub fn update(&mut self, renderer: &dyn PipelineRenderer) -> Vector3<N> {
+        if self.camera_controller.is_fit_pending() {
+            self.camera_controller
+                .fit_bounds(&mut self.camera, renderer.bounds());
+        }
+
+        let center = |camera: &Camera<N>, mode| match mode {
+            CameraMode::FirstPerson => camera.eye,
+            CameraMode::Orbit => camera.pivot,
+        };
+        let mode = self.camera_controller.mode();
+
+        let before = center(&self.camera, mode);
+        self.camera_controller.update_camera(&mut self.camera);
+        let moved = center(&self.camera, mode) - before;
+
+        self.camera_controller
+            .track(&mut self.camera, renderer.latest_keyframe());
+        moved
+    }
+
+    /// Moves the camera along with a linked one.
+    pub fn follow(&mut self, moved: Vector3<N>) {
+        self.camera.eye += moved;
+        self.camera.pivot += moved;
+    }
+
+    pub fn upload(&mut self, queue: &wgpu::Queue) {
+        let ViewportRect { width, height, .. } = self.rect;
+
+        self.uniforms.update(&self.camera, width, height);
+        self.uniforms.upload(queue);
+        self.gizmo.update(queue, &self.camera, width, height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> ViewportRect {
+        ViewportRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn single() {
+        assert_eq!(
+            ViewLayout::Single.rects(640, 480),
+            vec![rect(0, 0, 640, 480)]
+        );
+    }
+
+    #[test]
+    fn odd_sizes() {
+        // the last column and row take the odd pixels
+        assert_eq!(
+            ViewLayout::SPLIT.rects(641, 481),
+            vec![rect(0, 0, 320, 481), rect(320, 0, 321, 481)]
+        );
+        assert_eq!(
+            ViewLayout::QUAD.rects(641, 481),
+            vec![
+                rect(0, 0, 320, 240),
+                rect(320, 0, 321, 240),
+                rect(0, 240, 320, 241),
+                rect(320, 240, 321, 241),
+            ]
+        );
+    }
+
+    #[test]
+    fn cover_the_window() {
+        for &(width, height) in &[(640, 480), (641, 481), (3, 2), (2, 3)] {
+            for layout in &[ViewLayout::Single, ViewLayout::SPLIT, ViewLayout::QUAD] {
+                let rects = layout.rects(width, height);
+                assert_eq!(rects.len(), layout.cameras().len());
+
+                let area: u32 = rects.iter().map(|r| r.width * r.height).sum();
+                assert_eq!(area, width * height);
+                for r in &rects {
+                    assert!(r.x + r.width <= width && r.y + r.height <= height);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn one_pixel() {
+        // the viewports overlap rather than being empty, which wgpu rejects
+        assert_eq!(ViewLayout::QUAD.rects(1, 1), vec![rect(0, 0, 1, 1); 4]);
+        assert_eq!(
+            ViewLayout::SPLIT.rects(1, 5),
+            vec![rect(0, 0, 1, 5), rect(0, 0, 1, 5)]
+        );
+    }
+}